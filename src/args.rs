@@ -1,12 +1,17 @@
-use clap::Parser;
+use crate::config;
+use clap::{ArgGroup, Parser, Subcommand};
 use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "kinly", version)]
 pub struct Args {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     #[arg(
         default_value = "config.json",
         id = "path",
+        global = true,
         long = "config",
         help = "Path to config file (relative/absolute)"
     )]
@@ -15,6 +20,7 @@ pub struct Args {
     #[arg(
         default_value = "data",
         id = "data",
+        global = true,
         long = "data-dir",
         help = "Path to data directory (relative/absolute)"
     )]
@@ -23,10 +29,64 @@ pub struct Args {
     #[arg(
         default_value = "logs",
         id = "logs",
+        global = true,
         long = "logs-dir",
         help = "Path to logs directory (relative/absolute)"
     )]
     pub logs_dir: PathBuf,
+
+    #[arg(
+        global = true,
+        long = "log-format",
+        value_enum,
+        help = "Stdout log format, overrides logging.console.format from config"
+    )]
+    pub log_format: Option<config::logging::Format>,
+
+    #[arg(
+        global = true,
+        long = "watch-config",
+        help = "Reload config when the file changes, in addition to SIGHUP"
+    )]
+    pub watch_config: bool,
+}
+
+/// Diagnostic commands, the proxy is started when none is given.
+#[derive(Subcommand, Clone)]
+pub enum Command {
+    #[command(about = "Validate config and exit")]
+    Check,
+
+    #[command(about = "Generate signing keys, rotating existing ones")]
+    #[command(group = ArgGroup::new("keys").required(true).args(["server", "shared"]))]
+    Keygen {
+        #[arg(
+            long = "server",
+            help = "Server with its own keys_dir whose keys to rotate"
+        )]
+        server: Option<String>,
+
+        #[arg(
+            long = "shared",
+            help = "Rotate the shared keys used by every server without keys_dir"
+        )]
+        shared: bool,
+    },
+
+    #[command(about = "Print the public key advertised to authlib-injector")]
+    PublicKey {
+        #[arg(long = "server", help = "Server whose public key to print")]
+        server: Option<String>,
+
+        #[arg(long = "base64", help = "Print base64 DER instead of PEM")]
+        base64: bool,
+    },
+
+    #[command(about = "Connect to the server's LaunchServer and check its token")]
+    Ping {
+        #[arg(help = "Server name from config")]
+        server: String,
+    },
 }
 
 pub fn load() -> Args {
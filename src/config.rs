@@ -1,42 +1,449 @@
-use std::{fs, path::Path};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
 
 use figment::providers::{self, Format};
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de};
 use snafu::{ResultExt, Snafu};
 use tokio::io;
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct Config {
     pub binds: Binds,
+
+    /// Base URL the proxy is reached at, used for absolute URLs such as the API location.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_url: Option<url::Url>,
+
+    /// Server whose API location is advertised at `/`, the only server is used if absent.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_server: Option<String>,
+
+    /// Reverse proxies whose `Forwarded` and `X-Forwarded-*` headers are honored.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub trusted_proxies: Vec<ipnet::IpNet>,
+
+    /// Whether peers connected through unix domain socket binds are trusted proxies.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub trust_unix_peers: bool,
+
     pub servers: Vec<server::Server>,
+
+    #[serde(default)]
+    pub logging: logging::Logging,
 }
 
-#[derive(Serialize, Deserialize, Clone, Debug)]
-pub struct Binds {
-    pub host: std::net::Ipv4Addr,
-    pub port: u16,
+/// Addresses the proxy listens on, a single one or a list served by the same router.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum Binds {
+    One(Bind),
+    Many(Vec<Bind>),
+}
+
+impl Binds {
+    pub fn as_slice(&self) -> &[Bind] {
+        match self {
+            Binds::One(bind) => std::slice::from_ref(bind),
+            Binds::Many(binds) => binds,
+        }
+    }
+}
+
+// Untagged enums report any mistake as a mismatch of every variant, so binds are deserialized
+// by hand to name the offending bind and field.
+impl<'de> Deserialize<'de> for Binds {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct Visitor;
+
+        impl<'de> de::Visitor<'de> for Visitor {
+            type Value = Binds;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("a bind or a list of binds")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                BindVisitor.visit_str(value).map(Binds::One)
+            }
+
+            fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+                BindVisitor.visit_map(map).map(Binds::One)
+            }
+
+            fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut binds = Vec::new();
+                while let Some(bind) = seq.next_element::<Bind>()? {
+                    binds.push(bind);
+                }
+
+                Ok(Binds::Many(binds))
+            }
+        }
+
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(untagged)]
+pub enum Bind {
+    Tcp {
+        /// IPv4 or IPv6 address, `::` also accepts IPv4 connections on dual-stack hosts.
+        host: std::net::IpAddr,
+        port: u16,
+    },
+    Unix(UnixBind),
+}
+
+impl<'de> Deserialize<'de> for Bind {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(BindVisitor)
+    }
+}
+
+struct BindVisitor;
+
+impl<'de> de::Visitor<'de> for BindVisitor {
+    type Value = Bind;
+
+    fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str("an object with host and port, an object with unix, or \"unix:<path>\"")
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        match value.strip_prefix("unix:") {
+            Some(path) => Ok(Bind::Unix(UnixBind {
+                path: PathBuf::from(path),
+                mode: None,
+            })),
+            None => Err(E::invalid_value(de::Unexpected::Str(value), &self)),
+        }
+    }
+
+    fn visit_map<A: de::MapAccess<'de>>(self, map: A) -> Result<Self::Value, A::Error> {
+        #[derive(Deserialize)]
+        #[serde(deny_unknown_fields)]
+        struct Fields {
+            host: Option<std::net::IpAddr>,
+            port: Option<u16>,
+            unix: Option<PathBuf>,
+            mode: Option<String>,
+        }
+
+        let fields = Fields::deserialize(de::value::MapAccessDeserializer::new(map))?;
+        match fields {
+            Fields {
+                host: Some(host),
+                port: Some(port),
+                unix: None,
+                mode: None,
+            } => Ok(Bind::Tcp { host, port }),
+            Fields {
+                host: None,
+                port: None,
+                unix: Some(path),
+                mode,
+            } => Ok(Bind::Unix(UnixBind { path, mode })),
+            Fields { unix: Some(_), .. } => Err(de::Error::custom(
+                "unix cannot be combined with host or port",
+            )),
+            Fields { mode: Some(_), .. } => Err(de::Error::custom("mode requires unix")),
+            Fields { host: None, .. } => Err(de::Error::missing_field("host")),
+            Fields { port: None, .. } => Err(de::Error::missing_field("port")),
+        }
+    }
+}
+
+impl std::fmt::Display for Bind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Bind::Tcp { host, port } => write!(f, "{}", std::net::SocketAddr::from((*host, *port))),
+            Bind::Unix(unix) => write!(f, "unix:{}", unix.path.display()),
+        }
+    }
+}
+
+/// Unix domain socket, written as `"unix:/run/kinly.sock"` or as
+/// `{ "unix": "/run/kinly.sock", "mode": "660" }` to set its permissions.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(into = "UnixBindRepr")]
+pub struct UnixBind {
+    pub path: PathBuf,
+
+    /// Octal permissions of the socket file, left to the umask if absent.
+    pub mode: Option<String>,
+}
+
+impl UnixBind {
+    /// Returns the permission bits of [`UnixBind::mode`], `None` if absent or not octal.
+    pub fn permissions(&self) -> Option<u32> {
+        self.mode
+            .as_deref()
+            .and_then(|mode| u32::from_str_radix(mode, 8).ok())
+            .filter(|mode| *mode <= 0o7777)
+    }
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+enum UnixBindRepr {
+    Short(String),
+    Full {
+        unix: PathBuf,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        mode: Option<String>,
+    },
+}
+
+impl From<UnixBind> for UnixBindRepr {
+    fn from(bind: UnixBind) -> Self {
+        match bind.mode {
+            Some(mode) => UnixBindRepr::Full {
+                unix: bind.path,
+                mode: Some(mode),
+            },
+            None => UnixBindRepr::Short(format!("unix:{}", bind.path.display())),
+        }
+    }
+}
+
+pub mod logging {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+    pub struct Logging {
+        #[serde(default)]
+        pub console: Console,
+
+        #[serde(default)]
+        pub file: File,
+    }
+
+    /// Log output to stdout.
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+    #[serde(default)]
+    pub struct Console {
+        pub format: Format,
+    }
+
+    impl Default for Console {
+        fn default() -> Self {
+            Console {
+                format: Format::Full,
+            }
+        }
+    }
+
+    /// Log files written into the logs directory.
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+    #[serde(default)]
+    pub struct File {
+        pub level: Level,
+        pub format: Format,
+        pub rotation: Rotation,
+        /// Size a file may reach before the next one is started, with `size` rotation.
+        pub max_file_size_mb: u64,
+        /// Number of files kept, older ones are removed.
+        pub max_files: usize,
+    }
+
+    impl Default for File {
+        fn default() -> Self {
+            File {
+                level: Level::Debug,
+                format: Format::Full,
+                rotation: Rotation::Daily,
+                max_file_size_mb: 100,
+                max_files: 7,
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+    #[serde(rename_all = "snake_case")]
+    pub enum Level {
+        Trace,
+        Debug,
+        Info,
+        Warn,
+        Error,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, clap::ValueEnum)]
+    #[serde(rename_all = "snake_case")]
+    pub enum Format {
+        Full,
+        Compact,
+        /// One JSON object per line, including the fields of the enclosing spans.
+        Json,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+    #[serde(rename_all = "snake_case")]
+    pub enum Rotation {
+        Hourly,
+        Daily,
+        Size,
+    }
 }
 
 pub mod server {
     use serde::{Deserialize, Serialize};
+    use std::path::PathBuf;
 
-    #[derive(Serialize, Deserialize, Clone, Debug)]
+    #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
     pub struct Server {
         pub name: String,
         pub api: url::Url,
+
+        /// LaunchServer token, takes precedence over `token_file`.
+        #[serde(default, skip_serializing_if = "String::is_empty")]
         pub token: String,
+
+        /// File holding the LaunchServer token, e.g. a mounted Docker or Kubernetes secret.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub token_file: Option<PathBuf>,
+
+        #[serde(default = "default_auth_id")]
+        pub auth_id: String,
+
+        /// Directory with the server's own signing keys, the global one is used if absent.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub keys_dir: Option<PathBuf>,
+
         pub meta: meta::Meta,
+
+        #[serde(default)]
+        pub cache: cache::Cache,
+
+        #[serde(default)]
+        pub socket: socket::Socket,
+    }
+
+    fn default_auth_id() -> String {
+        "std".to_string()
+    }
+
+    pub mod cache {
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+        #[serde(default)]
+        pub struct Cache {
+            pub ttl_secs: u64,
+            pub negative_ttl_secs: u64,
+            pub max_entries: usize,
+        }
+
+        impl Default for Cache {
+            fn default() -> Self {
+                Cache {
+                    ttl_secs: 60,
+                    negative_ttl_secs: 10,
+                    max_entries: 10_000,
+                }
+            }
+        }
+    }
+
+    pub mod socket {
+        use serde::{Deserialize, Serialize};
+
+        /// Unset values fall back to the `launchserver::socket` defaults.
+        #[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+        pub struct Socket {
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub timeout_ms: Option<u64>,
+
+            #[serde(default)]
+            pub reconnect: Reconnect,
+
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub reconnect_wait_ms: Option<u64>,
+
+            /// Zero disables keepalive pings.
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub ping_interval_ms: Option<u64>,
+
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub pong_timeout_ms: Option<u64>,
+
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub capacity: Option<usize>,
+
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub concurrency: Option<usize>,
+        }
+
+        #[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+        pub struct Reconnect {
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub initial_delay_ms: Option<u64>,
+
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub multiplier: Option<f64>,
+
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub max_delay_ms: Option<u64>,
+
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub jitter: Option<f64>,
+
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub max_attempts: Option<u32>,
+        }
     }
 
     pub mod meta {
         use serde::{Deserialize, Serialize};
 
-        #[derive(Serialize, Deserialize, Clone, Debug)]
+        #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
         pub struct Meta {
             pub assets: Assets,
+
+            #[serde(default)]
+            pub links: Links,
+
+            #[serde(default)]
+            pub features: Features,
+        }
+
+        /// Links shown by launchers supporting authlib-injector.
+        #[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+        pub struct Links {
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub homepage: Option<url::Url>,
+
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub register: Option<url::Url>,
+        }
+
+        /// authlib-injector `feature.*` flags, unset ones are left to authlib-injector defaults.
+        #[derive(Serialize, Deserialize, Clone, Default, Debug, PartialEq)]
+        pub struct Features {
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub non_email_login: Option<bool>,
+
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub legacy_skin_api: Option<bool>,
+
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub no_mojang_namespace: Option<bool>,
+
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub enable_mojang_anti_features: Option<bool>,
+
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub enable_profile_key: Option<bool>,
+
+            #[serde(default, skip_serializing_if = "Option::is_none")]
+            pub username_check: Option<bool>,
         }
 
-        #[derive(Serialize, Deserialize, Clone, Debug)]
+        #[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
         #[serde(untagged)]
         pub enum Assets {
             AllInOne(Vec<String>),
@@ -50,6 +457,12 @@ pub mod server {
 
 #[derive(Debug, Snafu)]
 pub enum LoadConfigError {
+    #[snafu(display("unsupported config format {:?}, expected json, toml or yaml", path))]
+    UnsupportedFormat { path: PathBuf },
+
+    #[snafu(display("config {:?} not found", path))]
+    NotFound { path: PathBuf },
+
     #[snafu(display("writing new config"))]
     Write {
         #[snafu(source)]
@@ -61,6 +474,22 @@ pub enum LoadConfigError {
         #[snafu(source)]
         source: Box<figment::Error>,
     },
+
+    #[snafu(display("validating config:{}", problems.iter().map(|problem| format!("\n  - {}", problem)).collect::<String>()))]
+    Invalid { problems: Vec<Problem> },
+}
+
+/// A semantic config error located by the JSON path of the offending value.
+#[derive(Clone, Debug)]
+pub struct Problem {
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for Problem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
 }
 
 pub enum ConfigSource {
@@ -68,30 +497,679 @@ pub enum ConfigSource {
     Loaded(Config),
 }
 
+/// Config file format, chosen by the file extension.
+#[derive(Clone, Copy, Debug)]
+pub enum ConfigFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl ConfigFormat {
+    pub fn from_path(path: &Path) -> Result<ConfigFormat, LoadConfigError> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(|extension| extension.to_ascii_lowercase());
+
+        match extension.as_deref() {
+            Some("json") => Ok(ConfigFormat::Json),
+            Some("toml") => Ok(ConfigFormat::Toml),
+            Some("yaml" | "yml") => Ok(ConfigFormat::Yaml),
+            _ => UnsupportedFormatSnafu { path }.fail(),
+        }
+    }
+
+    fn provider(self, path: &Path) -> figment::Figment {
+        match self {
+            ConfigFormat::Json => figment::Figment::from(providers::Json::file(path)),
+            ConfigFormat::Toml => figment::Figment::from(providers::Toml::file(path)),
+            ConfigFormat::Yaml => figment::Figment::from(providers::Yaml::file(path)),
+        }
+    }
+
+    fn serialize(self, config: &Config) -> String {
+        match self {
+            ConfigFormat::Json => serde_json::to_string_pretty(config).unwrap(),
+            ConfigFormat::Toml => toml::to_string_pretty(config).unwrap(),
+            ConfigFormat::Yaml => serde_yaml::to_string(config).unwrap(),
+        }
+    }
+}
+
 pub fn load_or_create_config(path: &Path) -> Result<ConfigSource, LoadConfigError> {
+    let format = ConfigFormat::from_path(path)?;
+
     if !path.exists() {
         let config = Config {
-            binds: Binds {
+            binds: Binds::One(Bind::Tcp {
                 host: "0.0.0.0"
                     .parse()
                     .expect("The correct host should be parsed"),
                 port: 10000,
-            },
+            }),
+            public_url: None,
+            default_server: None,
+            trusted_proxies: Vec::default(),
+            trust_unix_peers: false,
             servers: Vec::default(),
+            logging: logging::Logging::default(),
         };
 
-        let serialized = serde_json::to_string_pretty(&config).unwrap();
-        fs::write(path, serialized).context(WriteSnafu)?;
+        fs::write(path, format.serialize(&config)).context(WriteSnafu)?;
 
         return Ok(ConfigSource::Created(config));
     }
 
-    let config = figment::Figment::new()
-        .join(providers::Json::file(path))
+    load_config(path).map(ConfigSource::Loaded)
+}
+
+/// Loads an existing config, failing instead of creating it when absent.
+///
+/// The format is chosen by the file extension, see [`ConfigFormat`].
+///
+/// Values from the file are overridden by `KINLY_`-prefixed environment variables, where `__`
+/// separates nested keys (`KINLY_BINDS__PORT`, or `KINLY_BINDS_PORT` for top-level sections) and
+/// servers are addressed by name (`KINLY_SERVERS__<name>__TOKEN`). Binds can only be overridden
+/// when the file holds a single one, as list items have no key to address them by.
+pub fn load_config(path: &Path) -> Result<Config, LoadConfigError> {
+    if !path.exists() {
+        return NotFoundSnafu { path }.fail();
+    }
+
+    let file = ConfigFormat::from_path(path)?.provider(path);
+
+    // Environment keys cannot address list items, an override would replace the whole list.
+    if matches!(
+        file.find_value("binds"),
+        Ok(figment::value::Value::Array(..))
+    ) && figment::Figment::from(global_env())
+        .find_value("binds")
+        .is_ok()
+    {
+        return InvalidSnafu {
+            problems: vec![Problem {
+                path: "binds".to_string(),
+                message: "KINLY_BINDS_* variables only override a single bind, not a list"
+                    .to_string(),
+            }],
+        }
+        .fail();
+    }
+
+    let mut config = file
+        .merge(global_env())
         .extract::<Config>()
         .map_err(|err| LoadConfigError::Extract {
             source: Box::new(err),
         })?;
 
-    Ok(ConfigSource::Loaded(config))
+    // Token problems are reported together with the ones found by validation.
+    let mut problems = Vec::new();
+    for (index, server) in config.servers.iter_mut().enumerate() {
+        // Servers are stored as a list, so their overrides are applied one by one.
+        *server = figment::Figment::from(providers::Serialized::defaults(&*server))
+            .merge(server_env(&server.name))
+            .extract::<server::Server>()
+            .map_err(|err| LoadConfigError::Extract {
+                source: Box::new(err),
+            })?;
+
+        problems.extend(resolve_token(index, server));
+    }
+
+    problems.extend(validate(&config));
+    if !problems.is_empty() {
+        return InvalidSnafu { problems }.fail();
+    }
+
+    Ok(config)
+}
+
+/// Paths served next to the per-server routes, which server names must not shadow.
+const RESERVED_NAMES: [&str; 3] = ["healthz", "readyz", "metrics"];
+
+/// Checks what deserialization cannot, returning every problem found.
+pub fn validate(config: &Config) -> Vec<Problem> {
+    let mut problems = Vec::new();
+    let mut problem = |path: String, message: String| problems.push(Problem { path, message });
+
+    let file = &config.logging.file;
+    if file.max_files == 0 {
+        problem(
+            "logging.file.max_files".to_string(),
+            "must be at least 1".to_string(),
+        );
+    }
+    if file.rotation == logging::Rotation::Size && file.max_file_size_mb == 0 {
+        problem(
+            "logging.file.max_file_size_mb".to_string(),
+            "must be at least 1 with size rotation".to_string(),
+        );
+    }
+
+    let binds = config.binds.as_slice();
+    if binds.is_empty() {
+        problem(
+            "binds".to_string(),
+            "must list at least one address".to_string(),
+        );
+    }
+    for (index, bind) in binds.iter().enumerate() {
+        if let Some(first) = binds[..index]
+            .iter()
+            .position(|other| other.to_string() == bind.to_string())
+        {
+            problem(
+                format!("binds[{}]", index),
+                format!("{} duplicates binds[{}]", bind, first),
+            );
+        }
+
+        if let Bind::Unix(unix) = bind {
+            if unix.path.as_os_str().is_empty() {
+                problem(
+                    format!("binds[{}]", index),
+                    "socket path must not be empty".to_string(),
+                );
+            }
+            if let Some(mode) = &unix.mode
+                && unix.permissions().is_none()
+            {
+                problem(
+                    format!("binds[{}].mode", index),
+                    format!("expected octal permissions such as 660, got {:?}", mode),
+                );
+            }
+        }
+    }
+
+    if let Some(public_url) = &config.public_url
+        && !matches!(public_url.scheme(), "http" | "https")
+    {
+        problem(
+            "public_url".to_string(),
+            format!(
+                "scheme must be http or https, got {:?} in {}",
+                public_url.scheme(),
+                public_url
+            ),
+        );
+    }
+
+    if let Some(default_server) = &config.default_server
+        && !config
+            .servers
+            .iter()
+            .any(|server| &server.name == default_server)
+    {
+        problem(
+            "default_server".to_string(),
+            format!("{:?} does not name a configured server", default_server),
+        );
+    }
+
+    // Names are compared case-insensitively, as environment overrides address servers that way.
+    let mut names = std::collections::HashMap::new();
+
+    for (index, server) in config.servers.iter().enumerate() {
+        let path = format!("servers[{}]", index);
+
+        if server.name.is_empty() {
+            problem(format!("{}.name", path), "must not be empty".to_string());
+        } else if server
+            .name
+            .chars()
+            .any(|char| char.is_whitespace() || matches!(char, '/' | '?' | '#' | '%'))
+        {
+            problem(
+                format!("{}.name", path),
+                format!(
+                    "{:?} must be usable as a URL path segment, without whitespace, '/', '?', \
+                     '#' or '%'",
+                    server.name
+                ),
+            );
+        } else if RESERVED_NAMES.contains(&server.name.as_str()) {
+            problem(
+                format!("{}.name", path),
+                format!(
+                    "{:?} is reserved for the /{} route",
+                    server.name, server.name
+                ),
+            );
+        }
+
+        if let Some(first) = names.insert(server.name.to_lowercase(), index) {
+            problem(
+                format!("{}.name", path),
+                format!(
+                    "{:?} duplicates servers[{}].name, names are compared case-insensitively",
+                    server.name, first
+                ),
+            );
+        }
+
+        if !matches!(server.api.scheme(), "ws" | "wss") {
+            problem(
+                format!("{}.api", path),
+                format!(
+                    "scheme must be ws or wss, got {:?} in {}",
+                    server.api.scheme(),
+                    server.api
+                ),
+            );
+        }
+
+        match &server.meta.assets {
+            server::meta::Assets::AllInOne(values) if values.is_empty() => problem(
+                format!("{}.meta.assets", path),
+                "must list at least one domain".to_string(),
+            ),
+            server::meta::Assets::Separated { skins, capes } => {
+                for (key, values) in [("skins", skins), ("capes", capes)] {
+                    if values.is_empty() {
+                        problem(
+                            format!("{}.meta.assets.{}", path, key),
+                            "must list at least one domain".to_string(),
+                        );
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        let reconnect = &server.socket.reconnect;
+        if reconnect
+            .multiplier
+            .is_some_and(|multiplier| multiplier < 1.0)
+        {
+            problem(
+                format!("{}.socket.reconnect.multiplier", path),
+                "must be at least 1.0".to_string(),
+            );
+        }
+        if reconnect
+            .jitter
+            .is_some_and(|jitter| !(0.0..=1.0).contains(&jitter))
+        {
+            problem(
+                format!("{}.socket.reconnect.jitter", path),
+                "must be between 0.0 and 1.0".to_string(),
+            );
+        }
+        if reconnect.max_attempts == Some(0) {
+            problem(
+                format!("{}.socket.reconnect.max_attempts", path),
+                "must be at least 1, omit it to retry forever".to_string(),
+            );
+        }
+    }
+
+    problems
+}
+
+/// Prefix of environment variables overriding the config file.
+const ENV_PREFIX: &str = "KINLY_";
+
+fn global_env() -> providers::Env {
+    providers::Env::prefixed(ENV_PREFIX)
+        .filter(|key| !key.starts_with("servers_"))
+        .map(|key| {
+            let key = key.as_str().to_ascii_lowercase().replace("__", ".");
+
+            match key.split_once('_') {
+                Some(("binds", field)) => format!("binds.{}", field).into(),
+                _ => key.into(),
+            }
+        })
+}
+
+fn server_env(name: &str) -> providers::Env {
+    providers::Env::prefixed(&format!("{}SERVERS__{}__", ENV_PREFIX, name)).split("__")
+}
+
+/// Fills in the token of the server at `index` from its `token_file` if needed.
+fn resolve_token(index: usize, server: &mut server::Server) -> Option<Problem> {
+    if !server.token.is_empty() {
+        return None;
+    }
+
+    let Some(path) = &server.token_file else {
+        return Some(Problem {
+            path: format!("servers[{}].token", index),
+            message: "must be set, either directly or through token_file".to_string(),
+        });
+    };
+
+    let token = match fs::read_to_string(path) {
+        Ok(token) => token,
+        Err(err) => {
+            return Some(Problem {
+                path: format!("servers[{}].token_file", index),
+                message: format!("reading {:?}: {}", path, err),
+            });
+        }
+    };
+
+    server.token = token.trim().to_string();
+    if server.token.is_empty() {
+        return Some(Problem {
+            path: format!("servers[{}].token_file", index),
+            message: format!("{:?} holds an empty token", path),
+        });
+    }
+
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{
+        Mutex,
+        atomic::{AtomicUsize, Ordering},
+    };
+
+    /// Serializes config loading, as environment overrides are process-wide.
+    static ENV: Mutex<()> = Mutex::new(());
+
+    const SERVER: &str = r#"{"name": "main", "api": "ws://127.0.0.1:9274/api", "token": "t",
+        "meta": {"assets": ["skins.example.com"]}}"#;
+
+    /// Creates an empty directory unique to the calling test.
+    fn temp_dir() -> PathBuf {
+        static COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+        let dir = std::env::temp_dir().join(format!(
+            "kinly-config-{}-{}",
+            std::process::id(),
+            COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    /// Loads `contents` as a config file named `name`, with the given environment overrides.
+    fn load(name: &str, contents: &str, env: &[(&str, &str)]) -> Result<Config, LoadConfigError> {
+        let dir = temp_dir();
+        let path = dir.join(name);
+        fs::write(&path, contents).unwrap();
+
+        let _guard = ENV.lock().unwrap_or_else(|err| err.into_inner());
+        // SAFETY: tests only touch the environment while holding `ENV`.
+        unsafe {
+            for (key, value) in env {
+                std::env::set_var(key, value);
+            }
+        }
+
+        let result = load_config(&path);
+
+        unsafe {
+            for (key, _) in env {
+                std::env::remove_var(key);
+            }
+        }
+        fs::remove_dir_all(&dir).unwrap();
+
+        result
+    }
+
+    fn problems(result: Result<Config, LoadConfigError>) -> Vec<String> {
+        match result {
+            Err(LoadConfigError::Invalid { problems }) => {
+                problems.iter().map(ToString::to_string).collect()
+            }
+            Err(err) => panic!("expected validation problems, got {:?}", err),
+            Ok(config) => panic!("expected validation problems, got {:?}", config),
+        }
+    }
+
+    /// Returns the key path and message of a deserialization error.
+    fn extract_error(result: Result<Config, LoadConfigError>) -> (String, String) {
+        match result {
+            Err(LoadConfigError::Extract { source }) => {
+                (source.path.join("."), source.kind.to_string())
+            }
+            Err(err) => panic!("expected a deserialization error, got {:?}", err),
+            Ok(config) => panic!("expected a deserialization error, got {:?}", config),
+        }
+    }
+
+    fn load_binds(binds: &str) -> Result<Config, LoadConfigError> {
+        load(
+            "config.json",
+            &format!(r#"{{"binds": {}, "servers": [{}]}}"#, binds, SERVER),
+            &[],
+        )
+    }
+
+    #[test]
+    fn parses_every_bind_form() {
+        let config = load(
+            "config.toml",
+            r#"
+            binds = [
+                { host = "::", port = 10000 },
+                "unix:/run/kinly.sock",
+                { unix = "/run/kinly-admin.sock", mode = "660" },
+            ]
+
+            [[servers]]
+            name = "main"
+            api = "ws://127.0.0.1:9274/api"
+            token = "t"
+            meta = { assets = ["skins.example.com"] }
+            "#,
+            &[],
+        );
+
+        assert_eq!(
+            config.unwrap().binds,
+            Binds::Many(vec![
+                Bind::Tcp {
+                    host: "::".parse().unwrap(),
+                    port: 10000
+                },
+                Bind::Unix(UnixBind {
+                    path: PathBuf::from("/run/kinly.sock"),
+                    mode: None
+                }),
+                Bind::Unix(UnixBind {
+                    path: PathBuf::from("/run/kinly-admin.sock"),
+                    mode: Some("660".to_string())
+                }),
+            ])
+        );
+    }
+
+    #[test]
+    fn names_failing_bind_and_field() {
+        assert_eq!(
+            extract_error(load_binds(
+                r#"[{"host": "127.0.0.1", "port": 10000}, {"host": "127.0.0.1", "port": "x"}]"#
+            )),
+            (
+                "binds.1.port".to_string(),
+                "invalid type: found string \"x\", expected u16".to_string()
+            )
+        );
+        assert_eq!(
+            extract_error(load_binds(r#"[{"host": "127.0.0.1"}]"#)),
+            ("binds.0".to_string(), "missing field `port`".to_string())
+        );
+        assert_eq!(
+            extract_error(load_binds(
+                r#"{"host": "127.0.0.1", "port": 10000, "prot": 1}"#
+            )),
+            (
+                "binds.prot".to_string(),
+                "unknown field: found `prot`, expected `one of `host`, `port`, `unix`, `mode``"
+                    .to_string()
+            )
+        );
+        assert_eq!(
+            extract_error(load_binds(r#""127.0.0.1:10000""#)),
+            (
+                "binds".to_string(),
+                "invalid value string \"127.0.0.1:10000\", expected an object with host \
+                 and port, an object with unix, or \"unix:<path>\""
+                    .to_string()
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_mixed_bind_kinds() {
+        assert_eq!(
+            extract_error(load_binds(
+                r#"[{"host": "127.0.0.1", "port": 10000}, {"unix": "/run/kinly.sock", "port": 1}]"#
+            )),
+            (
+                "binds.1".to_string(),
+                "unix cannot be combined with host or port".to_string()
+            )
+        );
+        assert_eq!(
+            extract_error(load_binds(
+                r#"{"host": "127.0.0.1", "port": 10000, "mode": "660"}"#
+            )),
+            ("binds".to_string(), "mode requires unix".to_string())
+        );
+    }
+
+    #[test]
+    fn overrides_single_bind_from_env() {
+        let config = load(
+            "config.json",
+            &format!(
+                r#"{{"binds": {{"host": "127.0.0.1", "port": 10000}}, "servers": [{}]}}"#,
+                SERVER
+            ),
+            &[("KINLY_BINDS_PORT", "10001")],
+        );
+
+        assert_eq!(
+            config.unwrap().binds,
+            Binds::One(Bind::Tcp {
+                host: "127.0.0.1".parse().unwrap(),
+                port: 10001
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_env_override_of_bind_list() {
+        let result = load(
+            "config.json",
+            &format!(
+                r#"{{"binds": [{{"host": "127.0.0.1", "port": 10000}}], "servers": [{}]}}"#,
+                SERVER
+            ),
+            &[("KINLY_BINDS_PORT", "10001")],
+        );
+
+        assert_eq!(
+            problems(result),
+            ["binds: KINLY_BINDS_* variables only override a single bind, not a list"]
+        );
+    }
+
+    #[test]
+    fn reports_every_problem_at_once() {
+        let result = load(
+            "config.json",
+            r#"{
+                "binds": [
+                    {"host": "127.0.0.1", "port": 10000},
+                    {"host": "127.0.0.1", "port": 10000}
+                ],
+                "default_server": "missing",
+                "logging": {"file": {"max_files": 0}},
+                "servers": [
+                    {"name": "a/b", "api": "http://127.0.0.1:9274/api", "meta": {"assets": []}},
+                    {
+                        "name": "A/B",
+                        "api": "ws://127.0.0.1:9274/api",
+                        "token_file": "/nonexistent/token",
+                        "meta": {"assets": {"skins": ["skins.example.com"], "capes": []}},
+                        "socket": {"reconnect": {"multiplier": 0.5, "max_attempts": 0}}
+                    }
+                ]
+            }"#,
+            &[],
+        );
+
+        assert_eq!(
+            problems(result),
+            [
+                "servers[0].token: must be set, either directly or through token_file",
+                "servers[1].token_file: reading \"/nonexistent/token\": No such file or \
+                 directory (os error 2)",
+                "logging.file.max_files: must be at least 1",
+                "binds[1]: 127.0.0.1:10000 duplicates binds[0]",
+                "default_server: \"missing\" does not name a configured server",
+                "servers[0].name: \"a/b\" must be usable as a URL path segment, without \
+                 whitespace, '/', '?', '#' or '%'",
+                "servers[0].api: scheme must be ws or wss, got \"http\" in \
+                 http://127.0.0.1:9274/api",
+                "servers[0].meta.assets: must list at least one domain",
+                "servers[1].name: \"A/B\" must be usable as a URL path segment, without \
+                 whitespace, '/', '?', '#' or '%'",
+                "servers[1].name: \"A/B\" duplicates servers[0].name, names are compared \
+                 case-insensitively",
+                "servers[1].meta.assets.capes: must list at least one domain",
+                "servers[1].socket.reconnect.multiplier: must be at least 1.0",
+                "servers[1].socket.reconnect.max_attempts: must be at least 1, omit it to retry \
+                 forever",
+            ]
+        );
+    }
+
+    #[test]
+    fn reads_token_file() {
+        let dir = temp_dir();
+        let token = dir.join("token");
+        let empty = dir.join("empty");
+        fs::write(&token, "secret\n").unwrap();
+        fs::write(&empty, " \n").unwrap();
+
+        let server = |name: &str, token_file: &Path| {
+            format!(
+                r#"{{"name": "{}", "api": "ws://127.0.0.1:9274/api", "token_file": {:?},
+                    "meta": {{"assets": ["skins.example.com"]}}}}"#,
+                name, token_file
+            )
+        };
+
+        let config = load(
+            "config.json",
+            &format!(
+                r#"{{"binds": {{"host": "127.0.0.1", "port": 10000}}, "servers": [{}]}}"#,
+                server("main", &token)
+            ),
+            &[],
+        )
+        .unwrap();
+        assert_eq!(config.servers[0].token, "secret");
+
+        let result = load(
+            "config.json",
+            &format!(
+                r#"{{"binds": [], "servers": [{}]}}"#,
+                server("main", &empty)
+            ),
+            &[],
+        );
+        assert_eq!(
+            problems(result),
+            [
+                format!("servers[0].token_file: {:?} holds an empty token", empty),
+                "binds: must list at least one address".to_string(),
+            ]
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
 }
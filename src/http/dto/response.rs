@@ -88,3 +88,62 @@ pub mod profile {
         }
     }
 }
+
+pub mod user {
+    use crate::http::dto::response::profile::property::Property;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug)]
+    pub struct User {
+        pub id: String,
+        pub properties: Vec<Property>,
+    }
+}
+
+pub mod error {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug)]
+    pub struct Error {
+        pub error: String,
+
+        #[serde(rename = "errorMessage")]
+        pub error_message: String,
+    }
+}
+
+pub mod health {
+    use serde::{Deserialize, Serialize};
+    use std::collections::BTreeMap;
+
+    #[derive(Serialize, Deserialize, Debug)]
+    pub struct Health {
+        pub ready: bool,
+        pub servers: BTreeMap<String, server::Server>,
+    }
+
+    pub mod server {
+        use serde::{Deserialize, Serialize};
+
+        #[derive(Serialize, Deserialize, Debug)]
+        #[serde(rename_all = "camelCase")]
+        pub struct Server {
+            pub state: State,
+
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub last_error: Option<String>,
+
+            /// Unix timestamp in milliseconds.
+            #[serde(skip_serializing_if = "Option::is_none")]
+            pub last_connected_at: Option<u128>,
+        }
+
+        #[derive(Serialize, Deserialize, Debug)]
+        #[serde(rename_all = "snake_case")]
+        pub enum State {
+            Connected,
+            Connecting,
+            Disconnected,
+        }
+    }
+}
@@ -0,0 +1,74 @@
+use crate::{
+    http::dto::response::error,
+    launchserver::{self, socket, types::response},
+};
+use axum::{
+    Json,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+};
+use snafu::Snafu;
+
+// The Error::LaunchServer variant is the heaviest one but also the most common, boxing it would
+// only add an allocation to every failed request.
+#[allow(clippy::large_enum_variant)]
+#[derive(Debug, Snafu)]
+#[snafu(visibility(pub(crate)))]
+pub enum Error {
+    #[snafu(display("Server '{server_id}' is not configured."))]
+    ServerNotFound { server_id: String },
+
+    #[snafu(display("{message}"))]
+    Forbidden { message: String },
+
+    #[snafu(display("LaunchServer request failed: {source}"))]
+    LaunchServer {
+        #[snafu(source)]
+        source: launchserver::error::Error,
+    },
+}
+
+impl Error {
+    /// Reports answers rejected by LaunchServer as [`Error::Forbidden`], keeping transport
+    /// failures distinguishable.
+    pub fn forbidden_if_rejected(
+        source: launchserver::error::Error,
+        message: impl Into<String>,
+    ) -> Error {
+        match source {
+            launchserver::error::Error::UnexpectedResponse(_) => Error::Forbidden {
+                message: message.into(),
+            },
+            source => Error::LaunchServer { source },
+        }
+    }
+}
+
+impl IntoResponse for Error {
+    fn into_response(self) -> Response {
+        let (status, kind) = match &self {
+            Error::ServerNotFound { .. } => (StatusCode::NOT_FOUND, "NotFoundException"),
+            Error::Forbidden { .. } => (StatusCode::FORBIDDEN, "ForbiddenOperationException"),
+            Error::LaunchServer { source } => match source {
+                // Yggdrasil answers unknown or unverified players with an empty response.
+                launchserver::error::Error::UnexpectedResponse(response::any::Kind::Error(
+                    response::error::Error {
+                        kind:
+                            response::error::Kind::UserNotFound | response::error::Kind::UserNotVerified,
+                    },
+                )) => return StatusCode::NO_CONTENT.into_response(),
+                launchserver::error::Error::Internal(socket::Error::ResponseNotReceived) => {
+                    (StatusCode::GATEWAY_TIMEOUT, "GatewayTimeoutException")
+                }
+                _ => (StatusCode::BAD_GATEWAY, "BadGatewayException"),
+            },
+        };
+
+        let body = error::Error {
+            error: kind.to_string(),
+            error_message: self.to_string(),
+        };
+
+        (status, Json(body)).into_response()
+    }
+}
@@ -1,10 +1,14 @@
 use crate::{
-    http::state::{ClonableState, Server, ServerKeyPair},
+    config::server::meta,
+    http::{
+        error::Error,
+        state::{ClonableState, Server, ServerKeyPair},
+    },
     launchserver,
 };
 use axum::{
     extract::{FromRequestParts, Path},
-    http::{StatusCode, request::Parts},
+    http::request::Parts,
     response::{IntoResponse, Response},
 };
 use serde::Deserialize;
@@ -23,6 +27,18 @@ impl CurrentServerHandle {
         &self.server.assets
     }
 
+    pub fn links(&self) -> &meta::Links {
+        &self.server.links
+    }
+
+    pub fn features(&self) -> &meta::Features {
+        &self.server.features
+    }
+
+    pub fn auth_id(&self) -> &str {
+        &self.server.auth_id
+    }
+
     pub fn client(&self) -> &launchserver::Client {
         &self.server.client
     }
@@ -39,10 +55,13 @@ impl FromRequestParts<ClonableState> for CurrentServerHandle {
             .await
             .map_err(|err| err.into_response())?;
 
-        let server = state
-            .servers
-            .get(&path.server_id)
-            .ok_or_else(|| StatusCode::NO_CONTENT.into_response())?;
+        let servers = state.servers.load();
+        let server = servers.get(&path.server_id).ok_or_else(|| {
+            Error::ServerNotFound {
+                server_id: path.server_id.clone(),
+            }
+            .into_response()
+        })?;
 
         Ok(CurrentServerHandle {
             server: Arc::clone(server),
@@ -0,0 +1,381 @@
+use crate::http::state::{ClonableState, State};
+use axum::{
+    extract::{ConnectInfo, Request, connect_info::Connected},
+    http::{HeaderMap, header},
+    middleware::Next,
+    response::Response,
+    serve::IncomingStream,
+};
+use std::net::{IpAddr, SocketAddr};
+use tokio::net::{TcpListener, UnixListener};
+
+/// The connecting peer.
+#[derive(Clone, Copy, Debug)]
+pub enum Peer {
+    Tcp(IpAddr),
+    /// Peer connected through a unix domain socket, which has no address. Its headers are
+    /// trusted according to `trust_unix_peers` rather than `trusted_proxies`.
+    Unix,
+}
+
+impl Connected<IncomingStream<'_, TcpListener>> for Peer {
+    fn connect_info(stream: IncomingStream<'_, TcpListener>) -> Self {
+        Peer::Tcp(stream.remote_addr().ip())
+    }
+}
+
+impl Connected<IncomingStream<'_, UnixListener>> for Peer {
+    fn connect_info(_stream: IncomingStream<'_, UnixListener>) -> Self {
+        Peer::Unix
+    }
+}
+
+/// Where a request came from, taking headers of trusted reverse proxies into account.
+#[derive(Clone, Debug)]
+pub struct Origin {
+    /// Address of the client, the connecting peer unless it is a trusted proxy.
+    pub client_ip: Option<IpAddr>,
+
+    /// Absolute URL or path, ending with `/`, that the proxy is reached at.
+    base: String,
+}
+
+impl Origin {
+    pub fn resolve(state: &State, peer: Option<Peer>, headers: &HeaderMap) -> Origin {
+        let routing = state.routing.load();
+        let is_trusted = |ip: &IpAddr| routing.trusted_proxies.iter().any(|net| net.contains(ip));
+
+        let peer_is_trusted = match peer {
+            Some(Peer::Tcp(ip)) => is_trusted(&ip),
+            Some(Peer::Unix) => routing.trust_unix_peers,
+            None => false,
+        };
+        let forwarded = match peer_is_trusted {
+            true => Forwarded::from_headers(headers),
+            false => Forwarded::default(),
+        };
+
+        // The chain is walked back from the peer, skipping trusted proxies. A unix peer has no
+        // address, so the walk starts with the last hop it reports.
+        let mut hops = forwarded.chain.iter().rev().copied();
+        let mut client_ip = match peer {
+            Some(Peer::Tcp(ip)) => Some(ip),
+            Some(Peer::Unix) => hops.next(),
+            None => None,
+        };
+        for ip in hops {
+            if !client_ip.is_some_and(|client_ip| is_trusted(&client_ip)) {
+                break;
+            }
+            client_ip = Some(ip);
+        }
+
+        let prefix = forwarded
+            .prefix
+            .as_deref()
+            .map(|prefix| prefix.trim_matches('/'))
+            .filter(|prefix| !prefix.is_empty())
+            .map(|prefix| format!("/{}", prefix))
+            .unwrap_or_default();
+
+        let base = match (&routing.public_url, &forwarded.host) {
+            (Some(public_url), _) => {
+                format!("{}/", public_url.as_str().trim_end_matches('/'))
+            }
+            (None, Some(host)) => {
+                let proto = forwarded.proto.as_deref().unwrap_or("http");
+                match url::Url::parse(&format!("{}://{}{}/", proto, host, prefix)) {
+                    Ok(url) => url.to_string(),
+                    Err(_) => format!("{}/", prefix),
+                }
+            }
+            (None, None) => format!("{}/", prefix),
+        };
+
+        Origin { client_ip, base }
+    }
+
+    /// Returns the API root of a server as advertised to authlib-injector.
+    pub fn api_location(&self, server_id: &str) -> String {
+        let mut url = url::Url::parse("http://localhost/").unwrap();
+        url.path_segments_mut()
+            .expect("http URLs have a path")
+            .push(server_id);
+
+        format!("{}{}/", self.base, url.path().trim_start_matches('/'))
+    }
+}
+
+/// Resolves the [`Origin`] of every request, making it available as an extension.
+pub async fn resolve_origin(
+    axum::extract::State(state): axum::extract::State<ClonableState>,
+    mut request: Request,
+    next: Next,
+) -> Response {
+    let peer = request
+        .extensions()
+        .get::<ConnectInfo<Peer>>()
+        .map(|ConnectInfo(peer)| *peer);
+
+    let origin = Origin::resolve(&state, peer, request.headers());
+    request.extensions_mut().insert(origin);
+
+    next.run(request).await
+}
+
+/// Values reported by reverse proxies, either through `Forwarded` or `X-Forwarded-*`.
+#[derive(Default)]
+struct Forwarded {
+    /// Addresses the request passed through, from the client to the last proxy.
+    chain: Vec<IpAddr>,
+    proto: Option<String>,
+    host: Option<String>,
+    prefix: Option<String>,
+}
+
+impl Forwarded {
+    fn from_headers(headers: &HeaderMap) -> Forwarded {
+        let values = |name| {
+            headers
+                .get_all(name)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .flat_map(|value| value.split(','))
+                .map(str::trim)
+                .filter(|value| !value.is_empty())
+                .collect::<Vec<_>>()
+        };
+
+        let prefix = values("x-forwarded-prefix")
+            .first()
+            .map(|prefix| prefix.to_string());
+
+        let elements = values(header::FORWARDED.as_str());
+        if !elements.is_empty() {
+            let mut forwarded = Forwarded {
+                prefix,
+                ..Forwarded::default()
+            };
+
+            for element in elements {
+                for pair in element.split(';') {
+                    let Some((key, value)) = pair.split_once('=') else {
+                        continue;
+                    };
+                    let value = value.trim().trim_matches('"');
+
+                    match key.trim().to_ascii_lowercase().as_str() {
+                        "for" => forwarded.chain.extend(parse_node(value)),
+                        "proto" if forwarded.proto.is_none() => {
+                            forwarded.proto = Some(value.to_string())
+                        }
+                        "host" if forwarded.host.is_none() => {
+                            forwarded.host = Some(value.to_string())
+                        }
+                        _ => {}
+                    }
+                }
+            }
+
+            return forwarded;
+        }
+
+        Forwarded {
+            chain: values("x-forwarded-for")
+                .into_iter()
+                .filter_map(parse_node)
+                .collect(),
+            proto: values("x-forwarded-proto")
+                .first()
+                .map(|proto| proto.to_string()),
+            host: values("x-forwarded-host")
+                .first()
+                .map(|host| host.to_string()),
+            prefix,
+        }
+    }
+}
+
+/// Parses a node such as `192.0.2.1`, `192.0.2.1:4711` or `[2001:db8::1]:4711`, skipping
+/// obfuscated and `unknown` ones.
+fn parse_node(node: &str) -> Option<IpAddr> {
+    if let Ok(ip) = node.parse::<IpAddr>() {
+        return Some(ip);
+    }
+
+    if let Some(rest) = node.strip_prefix('[') {
+        return rest.split_once(']')?.0.parse().ok();
+    }
+
+    node.parse::<SocketAddr>().ok().map(|addr| addr.ip())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::http::state::Routing;
+    use arc_swap::ArcSwap;
+    use axum::http::HeaderValue;
+    use std::collections::HashMap;
+
+    fn state(trusted_proxies: &[&str], trust_unix_peers: bool) -> State {
+        State {
+            servers: ArcSwap::from_pointee(HashMap::new()),
+            routing: ArcSwap::from_pointee(Routing {
+                public_url: None,
+                default_server: None,
+                trusted_proxies: trusted_proxies
+                    .iter()
+                    .map(|net| net.parse().unwrap())
+                    .collect(),
+                trust_unix_peers,
+            }),
+        }
+    }
+
+    fn headers(values: &[(&'static str, &'static str)]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in values {
+            headers.append(*name, HeaderValue::from_static(value));
+        }
+        headers
+    }
+
+    fn ip(value: &str) -> IpAddr {
+        value.parse().unwrap()
+    }
+
+    #[test]
+    fn parses_plain_and_bracketed_nodes() {
+        assert_eq!(parse_node("192.0.2.1"), Some(ip("192.0.2.1")));
+        assert_eq!(parse_node("192.0.2.1:4711"), Some(ip("192.0.2.1")));
+        assert_eq!(parse_node("2001:db8::1"), Some(ip("2001:db8::1")));
+        assert_eq!(parse_node("[2001:db8::1]"), Some(ip("2001:db8::1")));
+        assert_eq!(parse_node("[2001:db8::1]:4711"), Some(ip("2001:db8::1")));
+    }
+
+    #[test]
+    fn skips_obfuscated_and_unknown_nodes() {
+        assert_eq!(parse_node("unknown"), None);
+        assert_eq!(parse_node("_hidden"), None);
+        assert_eq!(parse_node("_SEVKISEK:4711"), None);
+        assert_eq!(parse_node("[2001:db8::1"), None);
+    }
+
+    #[test]
+    fn parses_quoted_forwarded_nodes() {
+        let forwarded = Forwarded::from_headers(&headers(&[(
+            "forwarded",
+            r#"for="[2001:db8:cafe::17]:4711";proto=https;host="auth.example.com", for=_hidden, For="192.0.2.43:47011""#,
+        )]));
+
+        assert_eq!(
+            forwarded.chain,
+            vec![ip("2001:db8:cafe::17"), ip("192.0.2.43")]
+        );
+        assert_eq!(forwarded.proto.as_deref(), Some("https"));
+        assert_eq!(forwarded.host.as_deref(), Some("auth.example.com"));
+    }
+
+    #[test]
+    fn forwarded_takes_precedence_over_x_forwarded() {
+        let forwarded = Forwarded::from_headers(&headers(&[
+            (
+                "forwarded",
+                "for=192.0.2.43;proto=https;host=auth.example.com",
+            ),
+            ("x-forwarded-for", "198.51.100.7"),
+            ("x-forwarded-proto", "http"),
+            ("x-forwarded-host", "other.example.com"),
+            ("x-forwarded-prefix", "/kinly"),
+        ]));
+
+        assert_eq!(forwarded.chain, vec![ip("192.0.2.43")]);
+        assert_eq!(forwarded.proto.as_deref(), Some("https"));
+        assert_eq!(forwarded.host.as_deref(), Some("auth.example.com"));
+        assert_eq!(forwarded.prefix.as_deref(), Some("/kinly"));
+    }
+
+    #[test]
+    fn falls_back_to_x_forwarded() {
+        let forwarded = Forwarded::from_headers(&headers(&[
+            ("x-forwarded-for", "198.51.100.7, [2001:db8::1]:4711"),
+            ("x-forwarded-for", "10.0.0.5"),
+            ("x-forwarded-proto", "https"),
+            ("x-forwarded-host", "auth.example.com"),
+        ]));
+
+        assert_eq!(
+            forwarded.chain,
+            vec![ip("198.51.100.7"), ip("2001:db8::1"), ip("10.0.0.5")]
+        );
+        assert_eq!(forwarded.proto.as_deref(), Some("https"));
+        assert_eq!(forwarded.host.as_deref(), Some("auth.example.com"));
+    }
+
+    #[test]
+    fn stops_at_first_untrusted_hop() {
+        let state = state(&["10.0.0.0/8"], false);
+        let headers = headers(&[("x-forwarded-for", "203.0.113.7, 198.51.100.2, 10.0.0.5")]);
+
+        let origin = Origin::resolve(&state, Some(Peer::Tcp(ip("10.0.0.1"))), &headers);
+
+        // 198.51.100.2 is not trusted, so the hop it reports cannot be believed.
+        assert_eq!(origin.client_ip, Some(ip("198.51.100.2")));
+    }
+
+    #[test]
+    fn ignores_headers_of_untrusted_peers() {
+        let state = state(&["10.0.0.0/8"], false);
+        let headers = headers(&[
+            ("x-forwarded-for", "203.0.113.7"),
+            ("x-forwarded-host", "evil.example.com"),
+        ]);
+
+        let origin = Origin::resolve(&state, Some(Peer::Tcp(ip("192.0.2.1"))), &headers);
+
+        assert_eq!(origin.client_ip, Some(ip("192.0.2.1")));
+        assert_eq!(origin.api_location("s"), "/s/");
+    }
+
+    #[test]
+    fn builds_base_from_trusted_headers() {
+        let state = state(&["127.0.0.1/32"], false);
+        let headers = headers(&[
+            (
+                "forwarded",
+                "for=203.0.113.7;proto=https;host=auth.example.com",
+            ),
+            ("x-forwarded-prefix", "/kinly/"),
+        ]);
+
+        let origin = Origin::resolve(&state, Some(Peer::Tcp(ip("127.0.0.1"))), &headers);
+
+        assert_eq!(origin.client_ip, Some(ip("203.0.113.7")));
+        assert_eq!(
+            origin.api_location("s"),
+            "https://auth.example.com/kinly/s/"
+        );
+    }
+
+    #[test]
+    fn trusts_unix_peers_only_if_configured() {
+        let headers = headers(&[("x-forwarded-for", "203.0.113.7, 10.0.0.5")]);
+
+        let origin = Origin::resolve(&state(&["10.0.0.0/8"], true), Some(Peer::Unix), &headers);
+        assert_eq!(origin.client_ip, Some(ip("203.0.113.7")));
+
+        let origin = Origin::resolve(&state(&["10.0.0.0/8"], false), Some(Peer::Unix), &headers);
+        assert_eq!(origin.client_ip, None);
+    }
+
+    #[test]
+    fn trusted_loopback_does_not_trust_unix_peers() {
+        let state = state(&["127.0.0.1/32"], false);
+        let headers = headers(&[("x-forwarded-host", "evil.example.com")]);
+
+        let origin = Origin::resolve(&state, Some(Peer::Unix), &headers);
+
+        assert_eq!(origin.api_location("s"), "/s/");
+    }
+}
@@ -0,0 +1,117 @@
+use crate::{
+    http::{forwarded::Origin, state::ClonableState},
+    metrics,
+};
+use axum::{
+    extract::{FromRequestParts, MatchedPath, RawPathParams, Request, State},
+    http::{HeaderName, HeaderValue, request::Parts},
+    middleware::Next,
+    response::Response,
+};
+use std::time::Instant;
+use tracing::{Instrument, debug, info_span};
+
+/// Header pointing authlib-injector at the API root (API Location Indication).
+pub const API_LOCATION_HEADER: HeaderName =
+    HeaderName::from_static("x-authlib-injector-api-location");
+
+/// Records the number and latency of handled requests by server and matched route, and runs
+/// the handler in a span carrying them.
+pub async fn observe_request(
+    State(state): State<ClonableState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+
+    // Only known servers become label values, so arbitrary paths cannot grow the series count.
+    let (mut parts, body) = request.into_parts();
+    let server_id = known_server_id(&mut parts, &state)
+        .await
+        .unwrap_or_else(|| "unknown".to_string());
+    let method = parts.method.clone();
+    let client_ip = parts
+        .extensions
+        .get::<Origin>()
+        .and_then(|origin| origin.client_ip)
+        .map(|ip| ip.to_string());
+    let request = Request::from_parts(parts, body);
+
+    let span = info_span!(
+        "http_request",
+        server_id = %server_id,
+        method = %method,
+        route = %route,
+        client_ip = client_ip.as_deref()
+    );
+
+    let started_at = Instant::now();
+    let response = next.run(request).instrument(span.clone()).await;
+
+    span.in_scope(|| {
+        debug!(
+            status = response.status().as_u16(),
+            elapsed_ms = started_at.elapsed().as_millis() as u64,
+            "request handled"
+        )
+    });
+
+    let metrics = metrics::get();
+    metrics
+        .http_requests
+        .with_label_values(&[
+            server_id.as_str(),
+            route.as_str(),
+            response.status().as_str(),
+        ])
+        .inc();
+    metrics
+        .http_request_duration
+        .with_label_values(&[server_id.as_str(), route.as_str()])
+        .observe(started_at.elapsed().as_secs_f64());
+
+    response
+}
+
+/// Advertises the API root of the requested server, so launchers accept any of its URLs.
+pub async fn indicate_api_location(
+    State(state): State<ClonableState>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let (mut parts, body) = request.into_parts();
+    let server_id = known_server_id(&mut parts, &state).await;
+    let origin = parts.extensions.get::<Origin>().cloned();
+    let request = Request::from_parts(parts, body);
+
+    let mut response = next.run(request).await;
+    if let (Some(server_id), Some(origin)) = (server_id, origin) {
+        set_api_location(&mut response, &origin, &server_id);
+    }
+
+    response
+}
+
+pub fn set_api_location(response: &mut Response, origin: &Origin, server_id: &str) {
+    if let Ok(value) = HeaderValue::from_str(&origin.api_location(server_id)) {
+        response.headers_mut().insert(API_LOCATION_HEADER, value);
+    }
+}
+
+/// Returns the `server_id` path parameter if it names a served server.
+async fn known_server_id(parts: &mut Parts, state: &ClonableState) -> Option<String> {
+    RawPathParams::from_request_parts(parts, state)
+        .await
+        .ok()
+        .and_then(|params| {
+            params
+                .iter()
+                .find(|(key, _)| *key == "server_id")
+                .map(|(_, value)| value.to_string())
+        })
+        .filter(|server_id| state.servers.load().contains_key(server_id))
+}
@@ -1,25 +1,62 @@
 use axum::Router;
-use routes::{api, root, sessionserver};
+use futures::future;
+use routes::{api, authserver, health, landing, metrics, root, sessionserver};
+use std::future::IntoFuture;
 use tokio::{io, net};
 
 pub mod dto;
+pub mod error;
 mod extractors;
+mod forwarded;
+mod middleware;
 mod routes;
 pub mod state;
 
-pub async fn init(
-    listener: net::TcpListener,
-    state: state::ClonableState,
-) -> Result<(), io::Error> {
+/// Socket the API is served on.
+pub enum Listener {
+    Tcp(net::TcpListener),
+    Unix(net::UnixListener),
+}
+
+/// Serves the API on every listener until one of them fails.
+pub async fn init(listeners: Vec<Listener>, state: state::ClonableState) -> Result<(), io::Error> {
+    let api_location =
+        axum::middleware::from_fn_with_state(state.clone(), middleware::indicate_api_location);
+
     let router = Router::new()
+        .merge(health::router())
+        .merge(metrics::router())
+        .merge(landing::router())
+        .merge(root::trailing_slash_router().route_layer(api_location.clone()))
         .nest(
             "/{server_id}",
             Router::new()
                 .merge(root::router())
                 .nest("/api", api::router())
-                .nest("/sessionserver", sessionserver::router()),
+                .nest("/authserver", authserver::router())
+                .nest("/sessionserver", sessionserver::router())
+                .route_layer(api_location),
         )
+        .route_layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::observe_request,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            forwarded::resolve_origin,
+        ))
         .with_state(state.clone());
 
-    axum::serve(listener, router).await
+    let service = router.into_make_service_with_connect_info::<forwarded::Peer>();
+
+    future::try_join_all(listeners.into_iter().map(|listener| match listener {
+        Listener::Tcp(listener) => {
+            future::Either::Left(axum::serve(listener, service.clone()).into_future())
+        }
+        Listener::Unix(listener) => {
+            future::Either::Right(axum::serve(listener, service.clone()).into_future())
+        }
+    }))
+    .await
+    .map(|_| ())
 }
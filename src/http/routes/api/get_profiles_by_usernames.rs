@@ -1,9 +1,11 @@
 use crate::http::{
     dto::response::profile::Profile,
+    error::{Error, LaunchServerSnafu},
     extractors::current_server::CurrentServerHandle,
 };
-use axum::{extract::Json, http::StatusCode, response::IntoResponse};
+use axum::extract::Json;
 use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
 
 #[derive(Deserialize)]
 pub struct GetProfilesByUsernamesRequest(pub Vec<String>);
@@ -14,14 +16,12 @@ pub struct GetProfilesByUsernamesResponse(pub Vec<Profile>);
 pub async fn get_profiles_by_usernames(
     current_server: CurrentServerHandle,
     Json(GetProfilesByUsernamesRequest(usernames)): Json<GetProfilesByUsernamesRequest>,
-) -> impl IntoResponse {
-    let Ok(profiles) = current_server
+) -> Result<Json<GetProfilesByUsernamesResponse>, Error> {
+    let profiles = current_server
         .client()
         .batch_profiles_by_usernames(usernames.clone())
         .await
-    else {
-        return StatusCode::NO_CONTENT.into_response();
-    };
+        .context(LaunchServerSnafu)?;
 
     let response = profiles
         .player_profiles
@@ -35,9 +35,5 @@ pub async fn get_profiles_by_usernames(
         })
         .collect::<Vec<_>>();
 
-    (
-        StatusCode::OK,
-        Json(GetProfilesByUsernamesResponse(response)),
-    )
-        .into_response()
+    Ok(Json(GetProfilesByUsernamesResponse(response)))
 }
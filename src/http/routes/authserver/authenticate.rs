@@ -0,0 +1,83 @@
+use crate::http::{
+    dto::response::{profile::Profile, user::User},
+    error::{Error, LaunchServerSnafu},
+    extractors::current_server::CurrentServerHandle,
+    routes::authserver::mapper::{map_profile, map_user},
+};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticateRequest {
+    pub username: String,
+    pub password: String,
+    pub client_token: Option<String>,
+
+    #[serde(default)]
+    pub request_user: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticateResponse {
+    pub access_token: String,
+    pub client_token: String,
+    pub available_profiles: Vec<Profile>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_profile: Option<Profile>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<User>,
+}
+
+pub async fn authenticate(
+    current_server: CurrentServerHandle,
+    Json(AuthenticateRequest {
+        username,
+        password,
+        client_token,
+        request_user,
+    }): Json<AuthenticateRequest>,
+) -> Result<Json<AuthenticateResponse>, Error> {
+    let mut session = current_server
+        .client()
+        .open_session()
+        .await
+        .context(LaunchServerSnafu)?;
+    let auth = session
+        .authenticate(current_server.auth_id(), username, password)
+        .await;
+    session.close().await;
+
+    let auth = auth.map_err(|err| {
+        Error::forbidden_if_rejected(err, "Invalid credentials. Invalid username or password.")
+    })?;
+
+    let Some(access_token) = auth
+        .oauth
+        .map(|oauth| oauth.access_token)
+        .or(auth.access_token)
+    else {
+        return Err(Error::Forbidden {
+            message: "LaunchServer did not issue an access token.".to_string(),
+        });
+    };
+
+    let response = AuthenticateResponse {
+        access_token,
+        client_token: client_token.unwrap_or_else(|| Uuid::new_v4().simple().to_string()),
+        available_profiles: auth.player_profile.iter().map(map_profile).collect(),
+        selected_profile: auth.player_profile.as_ref().map(map_profile),
+        user: auth
+            .player_profile
+            .as_ref()
+            .filter(|_| request_user)
+            .map(map_user),
+    };
+
+    Ok(Json(response))
+}
@@ -0,0 +1,30 @@
+use crate::http::extractors::current_server::CurrentServerHandle;
+use axum::{Json, http::StatusCode, response::IntoResponse};
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InvalidateRequest {
+    pub access_token: String,
+}
+
+pub async fn invalidate(
+    current_server: CurrentServerHandle,
+    Json(InvalidateRequest { access_token }): Json<InvalidateRequest>,
+) -> impl IntoResponse {
+    // Yggdrasil always answers 204 here, even if the token was already invalid.
+    if let Ok(mut session) = current_server.client().open_session().await {
+        // The session is restored on its own connection, so only this token is signed out.
+        if session
+            .restore_session(current_server.auth_id(), access_token, false)
+            .await
+            .is_ok()
+        {
+            let _ = session.exit(false).await;
+        }
+
+        session.close().await;
+    }
+
+    StatusCode::NO_CONTENT
+}
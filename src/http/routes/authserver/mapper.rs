@@ -0,0 +1,21 @@
+use crate::{
+    http::dto::response::{profile, user},
+    launchserver,
+};
+
+pub fn map_profile(
+    profile: &launchserver::types::response::base::profile::Profile,
+) -> profile::Profile {
+    profile::Profile {
+        id: profile.uuid.simple().to_string(),
+        name: profile.username.clone(),
+        properties: Vec::new(),
+    }
+}
+
+pub fn map_user(profile: &launchserver::types::response::base::profile::Profile) -> user::User {
+    user::User {
+        id: profile.uuid.simple().to_string(),
+        properties: Vec::new(),
+    }
+}
@@ -0,0 +1,27 @@
+mod authenticate;
+mod invalidate;
+mod mapper;
+mod refresh;
+mod signout;
+mod validate;
+
+use crate::http::state::ClonableState;
+use axum::{
+    Router,
+    routing::{MethodFilter, on},
+};
+
+pub fn router() -> Router<ClonableState> {
+    Router::new()
+        .route(
+            "/authenticate",
+            on(MethodFilter::POST, authenticate::authenticate),
+        )
+        .route("/refresh", on(MethodFilter::POST, refresh::refresh))
+        .route("/validate", on(MethodFilter::POST, validate::validate))
+        .route(
+            "/invalidate",
+            on(MethodFilter::POST, invalidate::invalidate),
+        )
+        .route("/signout", on(MethodFilter::POST, signout::signout))
+}
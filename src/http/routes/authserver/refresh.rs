@@ -0,0 +1,72 @@
+use crate::http::{
+    dto::response::{profile::Profile, user::User},
+    error::{Error, LaunchServerSnafu},
+    extractors::current_server::CurrentServerHandle,
+    routes::authserver::mapper::{map_profile, map_user},
+};
+use axum::Json;
+use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshRequest {
+    pub access_token: String,
+    pub client_token: Option<String>,
+
+    #[serde(default)]
+    pub request_user: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RefreshResponse {
+    pub access_token: String,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_token: Option<String>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selected_profile: Option<Profile>,
+
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<User>,
+}
+
+/// LaunchServer refresh tokens are never handed out to Yggdrasil clients, so LaunchServer's
+/// `refreshToken` cannot be called. Instead a refresh re-validates the access token through
+/// `restore` and intentionally returns it unchanged, which launchers accept as long as the
+/// token stays valid.
+pub async fn refresh(
+    current_server: CurrentServerHandle,
+    Json(RefreshRequest {
+        access_token,
+        client_token,
+        request_user,
+    }): Json<RefreshRequest>,
+) -> Result<Json<RefreshResponse>, Error> {
+    let mut session = current_server
+        .client()
+        .open_session()
+        .await
+        .context(LaunchServerSnafu)?;
+    let restore = session
+        .restore_session(current_server.auth_id(), access_token.clone(), true)
+        .await;
+    session.close().await;
+
+    let restore = restore.map_err(|err| Error::forbidden_if_rejected(err, "Invalid token."))?;
+
+    let profile = restore
+        .user_info
+        .and_then(|user_info| user_info.player_profile);
+
+    let response = RefreshResponse {
+        access_token,
+        client_token,
+        selected_profile: profile.as_ref().map(map_profile),
+        user: profile.as_ref().filter(|_| request_user).map(map_user),
+    };
+
+    Ok(Json(response))
+}
@@ -0,0 +1,39 @@
+use crate::http::{
+    error::{Error, LaunchServerSnafu},
+    extractors::current_server::CurrentServerHandle,
+};
+use axum::{Json, http::StatusCode};
+use serde::Deserialize;
+use snafu::ResultExt;
+
+#[derive(Deserialize)]
+pub struct SignoutRequest {
+    pub username: String,
+    pub password: String,
+}
+
+pub async fn signout(
+    current_server: CurrentServerHandle,
+    Json(SignoutRequest { username, password }): Json<SignoutRequest>,
+) -> Result<StatusCode, Error> {
+    let mut session = current_server
+        .client()
+        .open_session()
+        .await
+        .context(LaunchServerSnafu)?;
+
+    let result = match session
+        .authenticate(current_server.auth_id(), username, password)
+        .await
+    {
+        Ok(_) => session.exit(true).await.context(LaunchServerSnafu),
+        Err(err) => Err(Error::forbidden_if_rejected(
+            err,
+            "Invalid credentials. Invalid username or password.",
+        )),
+    };
+    session.close().await;
+    result?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,32 @@
+use crate::http::{
+    error::{Error, LaunchServerSnafu},
+    extractors::current_server::CurrentServerHandle,
+};
+use axum::{Json, http::StatusCode};
+use serde::Deserialize;
+use snafu::ResultExt;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidateRequest {
+    pub access_token: String,
+}
+
+pub async fn validate(
+    current_server: CurrentServerHandle,
+    Json(ValidateRequest { access_token }): Json<ValidateRequest>,
+) -> Result<StatusCode, Error> {
+    let mut session = current_server
+        .client()
+        .open_session()
+        .await
+        .context(LaunchServerSnafu)?;
+    let restore = session
+        .restore_session(current_server.auth_id(), access_token, false)
+        .await;
+    session.close().await;
+
+    restore.map_err(|err| Error::forbidden_if_rejected(err, "Invalid token."))?;
+
+    Ok(StatusCode::NO_CONTENT)
+}
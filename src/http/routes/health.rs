@@ -0,0 +1,73 @@
+use crate::{
+    http::{
+        dto::response::{health, health::server},
+        state::ClonableState,
+    },
+    launchserver::socket::ConnectionState,
+};
+use axum::{
+    Json,
+    Router,
+    extract::State,
+    http::StatusCode,
+    response::IntoResponse,
+    routing::{MethodFilter, on},
+};
+use std::{collections::BTreeMap, time::UNIX_EPOCH};
+
+pub fn router() -> Router<ClonableState> {
+    Router::new()
+        .route("/healthz", on(MethodFilter::GET, healthz))
+        .route("/readyz", on(MethodFilter::GET, readyz))
+}
+
+/// Liveness probe, answers as long as the HTTP server is running.
+async fn healthz(State(state): State<ClonableState>) -> impl IntoResponse {
+    (StatusCode::OK, Json(collect_health(&state)))
+}
+
+/// Readiness probe, fails unless every LaunchServer connection is established.
+async fn readyz(State(state): State<ClonableState>) -> impl IntoResponse {
+    let health = collect_health(&state);
+    let status = match health.ready {
+        true => StatusCode::OK,
+        false => StatusCode::SERVICE_UNAVAILABLE,
+    };
+
+    (status, Json(health))
+}
+
+fn collect_health(state: &ClonableState) -> health::Health {
+    let servers = state
+        .servers
+        .load()
+        .iter()
+        .map(|(name, server)| {
+            let status = server.client.status();
+
+            let server = server::Server {
+                state: match status.state {
+                    ConnectionState::Connected => server::State::Connected,
+                    ConnectionState::Connecting => server::State::Connecting,
+                    ConnectionState::Disconnected => server::State::Disconnected,
+                },
+                last_error: status.last_error,
+                last_connected_at: status.last_connected_at.and_then(|connected_at| {
+                    connected_at
+                        .duration_since(UNIX_EPOCH)
+                        .ok()
+                        .map(|since_epoch| since_epoch.as_millis())
+                }),
+            };
+
+            (name.clone(), server)
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    health::Health {
+        ready: servers
+            .values()
+            .all(|server| matches!(server.state, server::State::Connected)),
+        servers,
+    }
+}
@@ -0,0 +1,44 @@
+use crate::http::{forwarded::Origin, middleware, state::ClonableState};
+use axum::{
+    Extension,
+    Json,
+    Router,
+    extract::State,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{MethodFilter, on},
+};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LandingResponse {
+    /// API root of every server by name.
+    pub servers: BTreeMap<String, String>,
+}
+
+pub fn router() -> Router<ClonableState> {
+    Router::new().route("/", on(MethodFilter::GET, landing))
+}
+
+/// Lists the served servers, pointing authlib-injector at the default one, so the bare domain
+/// can be entered in launchers.
+async fn landing(
+    State(state): State<ClonableState>,
+    Extension(origin): Extension<Origin>,
+) -> Response {
+    let servers = state
+        .servers
+        .load()
+        .keys()
+        .map(|name| (name.clone(), origin.api_location(name)))
+        .collect();
+
+    let mut response = (StatusCode::OK, Json(LandingResponse { servers })).into_response();
+    if let Some(server_id) = state.default_server() {
+        middleware::set_api_location(&mut response, &origin, &server_id);
+    }
+
+    response
+}
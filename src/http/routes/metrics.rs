@@ -0,0 +1,19 @@
+use crate::{http::state::ClonableState, metrics};
+use axum::{
+    Router,
+    http::header,
+    response::IntoResponse,
+    routing::{MethodFilter, on},
+};
+
+pub fn router() -> Router<ClonableState> {
+    Router::new().route("/metrics", on(MethodFilter::GET, render))
+}
+
+/// Exposes collected metrics in the Prometheus text exposition format.
+async fn render() -> impl IntoResponse {
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        metrics::get().render(),
+    )
+}
@@ -1,3 +1,7 @@
 pub mod api;
+pub mod authserver;
+pub mod health;
+pub mod landing;
+pub mod metrics;
 pub mod root;
 pub mod sessionserver;
@@ -1,8 +1,8 @@
-use crate::http::state::ClonableState;
+use crate::http::{extractors::current_server::CurrentServerHandle, state::ClonableState};
 use axum::{
     Json,
     Router,
-    extract::{Path, State},
+    extract::Path,
     http::StatusCode,
     response::IntoResponse,
     routing::{MethodFilter, on},
@@ -19,7 +19,14 @@ pub struct RootPath {
 pub struct RootResponse {
     pub meta: meta::Meta,
     pub skin_domains: Vec<String>,
+
+    #[serde(rename = "signaturePublickey")]
     pub signature_public_key: String,
+
+    /// Every key signatures are accepted with, the previous key is kept here after a rotation
+    /// so textures signed before it stay valid.
+    #[serde(rename = "signaturePublickeys", skip_serializing_if = "Vec::is_empty")]
+    pub signature_public_keys: Vec<String>,
 }
 
 pub mod meta {
@@ -36,6 +43,60 @@ pub mod meta {
 
         #[serde(skip_serializing_if = "Option::is_none")]
         pub implementation_version: Option<String>,
+
+        #[serde(skip_serializing_if = "Links::is_empty")]
+        pub links: Links,
+
+        #[serde(
+            rename = "feature.non_email_login",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub non_email_login: Option<bool>,
+
+        #[serde(
+            rename = "feature.legacy_skin_api",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub legacy_skin_api: Option<bool>,
+
+        #[serde(
+            rename = "feature.no_mojang_namespace",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub no_mojang_namespace: Option<bool>,
+
+        #[serde(
+            rename = "feature.enable_mojang_anti_features",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub enable_mojang_anti_features: Option<bool>,
+
+        #[serde(
+            rename = "feature.enable_profile_key",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub enable_profile_key: Option<bool>,
+
+        #[serde(
+            rename = "feature.username_check",
+            skip_serializing_if = "Option::is_none"
+        )]
+        pub username_check: Option<bool>,
+    }
+
+    #[derive(Serialize)]
+    pub struct Links {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub homepage: Option<String>,
+
+        #[serde(skip_serializing_if = "Option::is_none")]
+        pub register: Option<String>,
+    }
+
+    impl Links {
+        fn is_empty(&self) -> bool {
+            self.homepage.is_none() && self.register.is_none()
+        }
     }
 }
 
@@ -43,23 +104,45 @@ pub fn router() -> Router<ClonableState> {
     Router::new().route("/", on(MethodFilter::GET, root))
 }
 
+/// Serves the API root with a trailing slash, which authlib-injector appends and the nested `/`
+/// route does not match.
+pub fn trailing_slash_router() -> Router<ClonableState> {
+    Router::new().route("/{server_id}/", on(MethodFilter::GET, root))
+}
+
 async fn root(
-    State(state): State<ClonableState>,
     Path(RootPath { server_id }): Path<RootPath>,
+    current_server: CurrentServerHandle,
 ) -> impl IntoResponse {
-    let Some(server) = state.servers.get(&server_id) else {
-        return StatusCode::NO_CONTENT.into_response();
-    };
+    let links = current_server.links();
+    let features = current_server.features();
 
     let response = RootResponse {
         meta: meta::Meta {
             server_name: Some(server_id),
             implementation_name: Some("Kinly".to_string()),
-            implementation_version: None,
+            implementation_version: Some(env!("CARGO_PKG_VERSION").to_string()),
+            links: meta::Links {
+                homepage: links.homepage.as_ref().map(|url| url.to_string()),
+                register: links.register.as_ref().map(|url| url.to_string()),
+            },
+            non_email_login: features.non_email_login,
+            legacy_skin_api: features.legacy_skin_api,
+            no_mojang_namespace: features.no_mojang_namespace,
+            enable_mojang_anti_features: features.enable_mojang_anti_features,
+            enable_profile_key: features.enable_profile_key,
+            username_check: features.username_check,
+        },
+        skin_domains: current_server.assets().to_vec(),
+        signature_public_key: current_server.keypair().public.clone(),
+        signature_public_keys: match &current_server.keypair().previous_public {
+            Some(previous_public) => vec![
+                current_server.keypair().public.clone(),
+                previous_public.clone(),
+            ],
+            None => Vec::new(),
         },
-        skin_domains: server.assets.clone(),
-        signature_public_key: server.key_pair.public.clone(),
     };
 
-    (StatusCode::OK, Json(response)).into_response()
+    (StatusCode::OK, Json(response))
 }
@@ -1,15 +1,15 @@
 use crate::http::{
     dto::response::profile::Profile,
+    error::{Error, LaunchServerSnafu},
     extractors::current_server::CurrentServerHandle,
     routes::sessionserver::mapper::map_profile,
 };
 use axum::{
     Json,
     extract::{Path, Query},
-    http::StatusCode,
-    response::IntoResponse,
 };
 use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Deserialize)]
@@ -29,14 +29,12 @@ pub async fn get_profile_by_uuid(
     Path(GetProfileByUuidPath { profile_id }): Path<GetProfileByUuidPath>,
     Query(GetProfileByUuidQuery { unsigned }): Query<GetProfileByUuidQuery>,
     current_server: CurrentServerHandle,
-) -> impl IntoResponse {
-    let Ok(profile) = current_server
+) -> Result<Json<GetProfileByUuidResponse>, Error> {
+    let profile = current_server
         .client()
         .get_profile_by_uuid(profile_id)
         .await
-    else {
-        return StatusCode::NO_CONTENT.into_response();
-    };
+        .context(LaunchServerSnafu)?;
 
     let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
     let response = map_profile(
@@ -46,5 +44,5 @@ pub async fn get_profile_by_uuid(
         !unsigned,
     );
 
-    (StatusCode::OK, Json(GetProfileByUuidResponse(response))).into_response()
+    Ok(Json(GetProfileByUuidResponse(response)))
 }
@@ -0,0 +1,36 @@
+use crate::http::{
+    dto::request::join::Body,
+    error::Error,
+    extractors::current_server::CurrentServerHandle,
+};
+use axum::{Json, http::StatusCode};
+
+pub async fn join(
+    current_server: CurrentServerHandle,
+    Json(Body {
+        access_token,
+        selected_profile,
+        server_id,
+    }): Json<Body>,
+) -> Result<StatusCode, Error> {
+    // LaunchServer identifies the joining player by username, Yggdrasil by profile UUID.
+    let profile = current_server
+        .client()
+        .get_profile_by_uuid(selected_profile)
+        .await
+        .map_err(|err| Error::forbidden_if_rejected(err, "Invalid token."))?;
+
+    let join_server = current_server
+        .client()
+        .join_server(profile.player_profile.username, access_token, server_id)
+        .await
+        .map_err(|err| Error::forbidden_if_rejected(err, "Invalid token."))?;
+
+    if !join_server.allow {
+        return Err(Error::Forbidden {
+            message: "Invalid token.".to_string(),
+        });
+    }
+
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -6,10 +6,11 @@ use crate::{
             textures::kind::{cape, skin, skin::metadata},
         },
     },
+    keypair,
     launchserver,
     launchserver::types::response::base::profile::skin::metadata::Model,
 };
-use openssl::{base64, pkey, rsa, rsa::Padding};
+use openssl::{base64, pkey, rsa};
 use std::time;
 
 pub fn map_profile(
@@ -43,13 +44,10 @@ pub fn map_profile(
 
     let encoded = base64::encode_block(serialized_textures.as_bytes());
     let encoded_signature = if signed {
-        let mut buf = vec![0u8; rsa.size() as usize];
+        let signature =
+            keypair::sign(rsa, encoded.as_bytes()).expect("key pair is verified at startup");
 
-        let len = rsa
-            .private_encrypt(encoded.as_bytes(), &mut buf, Padding::PKCS1)
-            .unwrap();
-
-        Some(base64::encode_block(&buf[..len]))
+        Some(base64::encode_block(&signature))
     } else {
         None
     };
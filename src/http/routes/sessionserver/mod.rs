@@ -1,4 +1,5 @@
 mod get_profile_by_uuid;
+mod join;
 mod mapper;
 mod player_has_joined;
 
@@ -12,6 +13,7 @@ pub fn router() -> Router<ClonableState> {
     Router::new().nest(
         "/session/minecraft",
         Router::new()
+            .route("/join", on(MethodFilter::POST, join::join))
             .route(
                 "/hasJoined",
                 on(MethodFilter::GET, player_has_joined::player_has_joined),
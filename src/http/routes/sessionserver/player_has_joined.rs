@@ -1,10 +1,12 @@
 use crate::http::{
     dto::response::profile::Profile,
+    error::{Error, LaunchServerSnafu},
     extractors::current_server::CurrentServerHandle,
     routes::sessionserver::mapper::map_profile,
 };
-use axum::{Json, extract::Query, http::StatusCode, response::IntoResponse};
+use axum::{Json, extract::Query};
 use serde::{Deserialize, Serialize};
+use snafu::ResultExt;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 #[derive(Deserialize)]
@@ -14,6 +16,8 @@ pub struct PlayerHasJoinedQuery {
     #[serde(rename = "serverId")]
     pub server_id: String,
 
+    // Accepted for Yggdrasil compatibility, LaunchServer does not verify the client address.
+    #[allow(dead_code)]
     pub ip: Option<String>,
 }
 
@@ -27,22 +31,18 @@ pub async fn player_has_joined(
         ip: _,
     }): Query<PlayerHasJoinedQuery>,
     current_server: CurrentServerHandle,
-) -> impl IntoResponse {
-    let Ok(check_server) = current_server
+) -> Result<Json<PlayerHasJoinedResponse>, Error> {
+    let check_server = current_server
         .client()
         .check_server(username.clone(), server_id.clone(), false, false)
         .await
-    else {
-        return StatusCode::NO_CONTENT.into_response();
-    };
+        .context(LaunchServerSnafu)?;
 
-    let Ok(profile) = current_server
+    let profile = current_server
         .client()
         .get_profile_by_uuid(check_server.uuid)
         .await
-    else {
-        return StatusCode::NO_CONTENT.into_response();
-    };
+        .context(LaunchServerSnafu)?;
 
     let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
     let response = map_profile(
@@ -52,5 +52,5 @@ pub async fn player_has_joined(
         false,
     );
 
-    (StatusCode::OK, Json(PlayerHasJoinedResponse(response))).into_response()
+    Ok(Json(PlayerHasJoinedResponse(response)))
 }
@@ -1,20 +1,60 @@
-use crate::launchserver;
+use crate::{config::server::meta, launchserver};
+use arc_swap::ArcSwap;
 use openssl::{pkey, rsa};
 use std::{collections::HashMap, sync::Arc};
 
 pub type ClonableState = Arc<State>;
 
+pub type Servers = HashMap<String, Arc<Server>>;
+
 pub struct State {
-    pub servers: HashMap<String, Arc<Server>>,
+    /// Served servers by name, replaced as a whole when the config is reloaded.
+    pub servers: ArcSwap<Servers>,
+
+    /// How the proxy is reached, replaced when the config is reloaded.
+    pub routing: ArcSwap<Routing>,
+}
+
+pub struct Routing {
+    pub public_url: Option<url::Url>,
+    pub default_server: Option<String>,
+
+    /// Peers whose `Forwarded` and `X-Forwarded-*` headers are honored.
+    pub trusted_proxies: Vec<ipnet::IpNet>,
+
+    /// Whether the headers of peers connected through unix domain sockets are honored.
+    pub trust_unix_peers: bool,
+}
+
+impl State {
+    /// Returns the server advertised at `/`, which is the only one if none is configured.
+    pub fn default_server(&self) -> Option<String> {
+        let servers = self.servers.load();
+
+        match &self.routing.load().default_server {
+            Some(name) => servers.contains_key(name).then(|| name.clone()),
+            None if servers.len() == 1 => servers.keys().next().cloned(),
+            None => None,
+        }
+    }
 }
 
 pub struct Server {
     pub key_pair: ServerKeyPair,
     pub assets: Vec<String>,
-    pub client: launchserver::Client,
+    pub links: meta::Links,
+    pub features: meta::Features,
+    pub auth_id: String,
+
+    /// LaunchServer client, shared with the next build of the server if its connection
+    /// settings survive a reload.
+    pub client: Arc<launchserver::Client>,
 }
 
 pub struct ServerKeyPair {
     pub private: rsa::Rsa<pkey::Private>,
     pub public: String,
+
+    /// Public key replaced by the last rotation, still advertised to clients.
+    pub previous_public: Option<String>,
 }
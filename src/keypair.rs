@@ -2,10 +2,10 @@ use std::{
     fs,
     io::{self, Write},
     os::unix::fs::OpenOptionsExt,
-    path::Path,
+    path::{Path, PathBuf},
 };
 
-use openssl::{pkey, rsa};
+use openssl::{hash, pkey, rsa, sign};
 use snafu::{ResultExt, Snafu};
 
 #[derive(Debug, Snafu)]
@@ -27,30 +27,83 @@ pub enum LoadKeyPairError {
         #[snafu(source)]
         source: LoadPrivateKeyError,
     },
+
+    #[snafu(display("no private key in {:?}, generate one with keygen", dir))]
+    Missing { dir: PathBuf },
+
+    #[snafu(display("moving current private key aside"))]
+    Rotate {
+        #[snafu(source)]
+        source: io::Error,
+    },
 }
 
+const CURRENT_KEY_FILE: &str = "private.pem";
+const PREVIOUS_KEY_FILE: &str = "previous.pem";
+
+#[derive(Clone)]
 pub struct KeyPair {
     pub private: rsa::Rsa<pkey::Private>,
     pub public: String,
+
+    /// Public key replaced by the last rotation, if any.
+    pub previous_public: Option<String>,
 }
 
 pub fn load_or_create_key_pair(dir: &Path) -> Result<KeyPair, LoadKeyPairError> {
     fs::create_dir_all(dir).context(DirCreationSnafu)?;
 
-    let private_key_path = dir.join("private.pem");
-    let private_key = match private_key_path.exists() {
-        true => load_private_key(&private_key_path).context(LoadSnafu)?,
-        false => write_private_key(&private_key_path).context(WriteSnafu)?,
-    };
+    let private_key_path = dir.join(CURRENT_KEY_FILE);
+    if !private_key_path.exists() {
+        write_private_key(&private_key_path).context(WriteSnafu)?;
+    }
+
+    load_key_pair(dir)
+}
 
-    let public_key = String::from_utf8(private_key.public_key_to_pem().unwrap()).unwrap();
+/// Loads the existing key pair from `dir` without creating anything.
+pub fn load_key_pair(dir: &Path) -> Result<KeyPair, LoadKeyPairError> {
+    let private_key_path = dir.join(CURRENT_KEY_FILE);
+    if !private_key_path.exists() {
+        return MissingSnafu { dir }.fail();
+    }
+
+    let private_key = load_private_key(&private_key_path).context(LoadSnafu)?;
+
+    let previous_key_path = dir.join(PREVIOUS_KEY_FILE);
+    let previous_key = match previous_key_path.exists() {
+        true => Some(load_private_key(&previous_key_path).context(LoadSnafu)?),
+        false => None,
+    };
 
     Ok(KeyPair {
+        public: public_key_to_pem(&private_key),
         private: private_key,
-        public: public_key,
+        previous_public: previous_key.as_ref().map(public_key_to_pem),
     })
 }
 
+/// Keeps the current private key as the previous one and generates a new current key.
+///
+/// The previous public key is advertised next to the current one, so clients keep trusting
+/// signatures made before the rotation. A key kept from an earlier rotation is discarded.
+pub fn rotate_key_pair(dir: &Path) -> Result<KeyPair, LoadKeyPairError> {
+    fs::create_dir_all(dir).context(DirCreationSnafu)?;
+
+    let private_key_path = dir.join(CURRENT_KEY_FILE);
+    if private_key_path.exists() {
+        fs::rename(&private_key_path, dir.join(PREVIOUS_KEY_FILE)).context(RotateSnafu)?;
+    }
+
+    write_private_key(&private_key_path).context(WriteSnafu)?;
+
+    load_or_create_key_pair(dir)
+}
+
+fn public_key_to_pem(private_key: &rsa::Rsa<pkey::Private>) -> String {
+    String::from_utf8(private_key.public_key_to_pem().unwrap()).unwrap()
+}
+
 #[derive(Debug, Snafu)]
 pub enum WritePrivateKeyError {
     #[snafu(display("generating private key"))]
@@ -101,3 +154,51 @@ fn load_private_key(path: &Path) -> Result<rsa::Rsa<pkey::Private>, LoadPrivateK
     let key_data = fs::read(path).context(ReadPrivateKeySnafu)?;
     rsa::Rsa::private_key_from_pem(&key_data).context(MalformedPrivateKeySnafu)
 }
+
+/// Signs `data` with `SHA1withRSA`, the scheme expected for Yggdrasil profile properties.
+pub fn sign(
+    private: &rsa::Rsa<pkey::Private>,
+    data: &[u8],
+) -> Result<Vec<u8>, openssl::error::ErrorStack> {
+    let key = pkey::PKey::from_rsa(private.clone())?;
+
+    let mut signer = sign::Signer::new(hash::MessageDigest::sha1(), &key)?;
+    signer.update(data)?;
+    signer.sign_to_vec()
+}
+
+#[derive(Debug, Snafu)]
+pub enum VerifyKeyPairError {
+    #[snafu(display("signing test payload"))]
+    SignPayload {
+        #[snafu(source)]
+        source: openssl::error::ErrorStack,
+    },
+
+    #[snafu(display("verifying test payload signature"))]
+    VerifyPayload {
+        #[snafu(source)]
+        source: openssl::error::ErrorStack,
+    },
+
+    #[snafu(display("signature does not match the public key"))]
+    SignatureMismatch,
+}
+
+/// Signs a test payload with the private key and verifies it against the advertised public key.
+pub fn verify_key_pair(key_pair: &KeyPair) -> Result<(), VerifyKeyPairError> {
+    const PAYLOAD: &[u8] = b"kinly key pair self-test";
+
+    let signature = sign(&key_pair.private, PAYLOAD).context(SignPayloadSnafu)?;
+
+    let public =
+        pkey::PKey::public_key_from_pem(key_pair.public.as_bytes()).context(VerifyPayloadSnafu)?;
+    let mut verifier =
+        sign::Verifier::new(hash::MessageDigest::sha1(), &public).context(VerifyPayloadSnafu)?;
+    verifier.update(PAYLOAD).context(VerifyPayloadSnafu)?;
+
+    match verifier.verify(&signature).context(VerifyPayloadSnafu)? {
+        true => Ok(()),
+        false => Err(VerifyKeyPairError::SignatureMismatch),
+    }
+}
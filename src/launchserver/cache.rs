@@ -0,0 +1,195 @@
+use std::{
+    collections::{BTreeMap, HashMap},
+    hash::Hash,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+#[derive(Clone, Debug)]
+pub struct CacheOptions {
+    /// How long a found value stays cached. Zero disables caching.
+    pub ttl: Duration,
+    /// How long a "not found" answer stays cached. Zero disables negative caching.
+    pub negative_ttl: Duration,
+    /// Upper bound of cached entries, the ones closest to expiration are evicted first.
+    pub max_entries: usize,
+}
+
+struct Entry<V> {
+    value: Option<V>,
+    /// Position of the entry in [`Entries::expirations`].
+    expiration: Expiration,
+}
+
+/// Expiration time, made unique by an insertion counter.
+type Expiration = (Instant, u64);
+
+struct Entries<K, V> {
+    values: HashMap<K, Entry<V>>,
+    /// Keys ordered by expiration, so expired and evicted entries are found without a scan.
+    expirations: BTreeMap<Expiration, K>,
+    inserted: u64,
+}
+
+impl<K: Eq + Hash + Clone, V> Entries<K, V> {
+    fn remove(&mut self, key: &K) {
+        if let Some(entry) = self.values.remove(key) {
+            self.expirations.remove(&entry.expiration);
+        }
+    }
+
+    /// Removes the entry closest to expiration.
+    fn pop_first(&mut self) {
+        if let Some((_, key)) = self.expirations.pop_first() {
+            self.values.remove(&key);
+        }
+    }
+
+    fn first_expires_at(&self) -> Option<Instant> {
+        self.expirations
+            .first_key_value()
+            .map(|((expires_at, _), _)| *expires_at)
+    }
+}
+
+/// In-memory TTL cache where `None` values represent cached "not found" answers.
+pub struct Cache<K, V> {
+    entries: Mutex<Entries<K, V>>,
+    options: CacheOptions,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Cache<K, V> {
+    pub fn new(options: CacheOptions) -> Cache<K, V> {
+        Cache {
+            entries: Mutex::new(Entries {
+                values: HashMap::new(),
+                expirations: BTreeMap::new(),
+                inserted: 0,
+            }),
+            options,
+        }
+    }
+
+    /// Returns `Some(None)` for a cached miss and `None` if nothing is cached.
+    pub fn get(&self, key: &K) -> Option<Option<V>> {
+        let mut entries = self.entries.lock().unwrap();
+
+        let entry = entries.values.get(key)?;
+        if entry.expiration.0 <= Instant::now() {
+            entries.remove(key);
+            return None;
+        }
+
+        Some(entry.value.clone())
+    }
+
+    pub fn insert(&self, key: K, value: Option<V>) {
+        let ttl = match value {
+            Some(_) => self.options.ttl,
+            None => self.options.negative_ttl,
+        };
+        if ttl.is_zero() || self.options.max_entries == 0 {
+            return;
+        }
+
+        let now = Instant::now();
+        let mut entries = self.entries.lock().unwrap();
+
+        entries.remove(&key);
+        while entries
+            .first_expires_at()
+            .is_some_and(|expires_at| expires_at <= now)
+        {
+            entries.pop_first();
+        }
+        if entries.values.len() >= self.options.max_entries {
+            entries.pop_first();
+        }
+
+        entries.inserted += 1;
+        let expiration = (now + ttl, entries.inserted);
+        entries.expirations.insert(expiration, key.clone());
+        entries.values.insert(key, Entry { value, expiration });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    const SHORT_TTL: Duration = Duration::from_millis(20);
+    const LONG_TTL: Duration = Duration::from_secs(3600);
+
+    fn cache(
+        ttl: Duration,
+        negative_ttl: Duration,
+        max_entries: usize,
+    ) -> Cache<&'static str, u32> {
+        Cache::new(CacheOptions {
+            ttl,
+            negative_ttl,
+            max_entries,
+        })
+    }
+
+    fn len(cache: &Cache<&'static str, u32>) -> (usize, usize) {
+        let entries = cache.entries.lock().unwrap();
+        (entries.values.len(), entries.expirations.len())
+    }
+
+    #[test]
+    fn removes_expired_entry_on_get() {
+        let cache = cache(SHORT_TTL, SHORT_TTL, 10);
+        cache.insert("a", Some(1));
+        assert_eq!(cache.get(&"a"), Some(Some(1)));
+
+        thread::sleep(SHORT_TTL * 2);
+
+        assert_eq!(cache.get(&"a"), None);
+        assert_eq!(len(&cache), (0, 0));
+    }
+
+    #[test]
+    fn negative_entries_expire_on_their_own_ttl() {
+        let cache = cache(LONG_TTL, SHORT_TTL, 10);
+        cache.insert("found", Some(1));
+        cache.insert("missing", None);
+        assert_eq!(cache.get(&"missing"), Some(None));
+
+        thread::sleep(SHORT_TTL * 2);
+
+        assert_eq!(cache.get(&"missing"), None);
+        assert_eq!(cache.get(&"found"), Some(Some(1)));
+    }
+
+    #[test]
+    fn evicts_entry_closest_to_expiry_at_capacity() {
+        let cache = cache(LONG_TTL, LONG_TTL / 2, 2);
+        cache.insert("a", Some(1));
+        cache.insert("b", None);
+        cache.insert("c", Some(3));
+
+        assert_eq!(cache.get(&"a"), Some(Some(1)));
+        assert_eq!(cache.get(&"b"), None);
+        assert_eq!(cache.get(&"c"), Some(Some(3)));
+        assert_eq!(len(&cache), (2, 2));
+    }
+
+    #[test]
+    fn reinserting_key_replaces_its_expiration() {
+        let cache = cache(LONG_TTL, LONG_TTL, 2);
+        cache.insert("a", Some(1));
+        cache.insert("b", Some(2));
+        cache.insert("a", Some(10));
+        assert_eq!(len(&cache), (2, 2));
+
+        // "a" now expires after "b", so "b" is the one evicted.
+        cache.insert("c", Some(3));
+
+        assert_eq!(cache.get(&"a"), Some(Some(10)));
+        assert_eq!(cache.get(&"b"), None);
+        assert_eq!(cache.get(&"c"), Some(Some(3)));
+        assert_eq!(len(&cache), (2, 2));
+    }
+}
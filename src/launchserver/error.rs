@@ -3,12 +3,29 @@ use std::fmt::{Display, Formatter};
 
 // The Error::UnexpectedResponse variant, although much heavier than Error::Internal, is the most common variant.
 #[allow(clippy::large_enum_variant)]
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum Error {
     UnexpectedResponse(response::any::Kind),
     Internal(socket::Error),
 }
 
+impl Error {
+    pub fn user_not_found() -> Error {
+        Error::UnexpectedResponse(response::any::Kind::Error(response::error::Error {
+            kind: response::error::Kind::UserNotFound,
+        }))
+    }
+
+    pub fn is_user_not_found(&self) -> bool {
+        matches!(
+            self,
+            Error::UnexpectedResponse(response::any::Kind::Error(response::error::Error {
+                kind: response::error::Kind::UserNotFound,
+            }))
+        )
+    }
+}
+
 impl std::error::Error for Error {}
 
 impl Display for Error {
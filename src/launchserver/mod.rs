@@ -1,12 +1,3 @@
-pub mod error;
-pub mod socket;
-pub mod types;
-
-use crate::launchserver::types::{request, response};
-use futures_util::TryFutureExt;
-use std::{collections::HashMap, time::Duration};
-use uuid::Uuid;
-
 macro_rules! extract_response {
     ($response:expr, $kind:path) => {
         if let $kind(value) = $response {
@@ -17,28 +8,52 @@ macro_rules! extract_response {
     };
 }
 
+pub mod cache;
+pub mod error;
+pub mod session;
+mod singleflight;
+pub mod socket;
+pub mod types;
+
+use crate::{
+    launchserver::types::{request, response, response::base::profile::Profile},
+    metrics,
+};
+use futures_util::TryFutureExt;
+use std::{collections::HashMap, time::Duration};
+use uuid::Uuid;
+
 pub struct Client {
     token: String,
+    addr: url::Url,
     timeout: Duration,
     socket: socket::Socket,
+
+    profiles_by_uuid: cache::Cache<Uuid, Profile>,
+    profiles_by_username: cache::Cache<String, Profile>,
+
+    in_flight: singleflight::SingleFlight<String, Result<response::any::Kind, error::Error>>,
 }
 
 impl Client {
     pub fn new(
         token: impl Into<String>,
         addr: impl Into<url::Url>,
-        timeout: impl Into<Option<Duration>>,
+        options: socket::SocketOptions,
+        cache: cache::CacheOptions,
     ) -> Client {
-        let timeout = timeout.into();
-        let options = socket::SocketOptions::builder()
-            .with_timeout(timeout)
-            .with_reconnection_timeout(timeout)
-            .build();
+        let addr = addr.into();
 
         Client {
             token: token.into(),
             timeout: options.timeout,
-            socket: socket::Socket::new(addr, options),
+            socket: socket::Socket::new(addr.clone(), options),
+            addr,
+
+            profiles_by_uuid: cache::Cache::new(cache.clone()),
+            profiles_by_username: cache::Cache::new(cache),
+
+            in_flight: singleflight::SingleFlight::new(),
         }
     }
 
@@ -67,20 +82,49 @@ impl Client {
         extract_response!(response, response::any::Kind::CheckServer)
     }
 
-    pub async fn get_profile_by_uuid(
+    pub async fn join_server(
         &self,
-        uuid: Uuid,
-    ) -> Result<response::get_profile_by_uuid::GetProfileByUuid, error::Error> {
+        username: impl Into<String>,
+        access_token: impl Into<String>,
+        server_id: impl Into<String>,
+    ) -> Result<response::join_server::JoinServer, error::Error> {
         let response = self
             .send_safely_request(request::Request {
                 id: Uuid::new_v4(),
-                body: request::any::Kind::GetProfileByUuid(
-                    request::get_profile_by_uuid::GetProfileByUuid { uuid },
-                ),
+                body: request::any::Kind::JoinServer(request::join_server::JoinServer {
+                    username: username.into(),
+                    access_token: access_token.into(),
+                    server_id: server_id.into(),
+                }),
             })
             .await?;
 
-        extract_response!(response, response::any::Kind::GetProfileByUuid)
+        extract_response!(response, response::any::Kind::JoinServer)
+    }
+
+    pub async fn get_profile_by_uuid(
+        &self,
+        uuid: Uuid,
+    ) -> Result<response::get_profile_by_uuid::GetProfileByUuid, error::Error> {
+        let cached = self.profiles_by_uuid.get(&uuid);
+        self.record_cache_lookup("profile_by_uuid", cached.is_some());
+
+        match cached {
+            Some(Some(player_profile)) => {
+                return Ok(response::get_profile_by_uuid::GetProfileByUuid { player_profile });
+            }
+            Some(None) => return Err(error::Error::user_not_found()),
+            None => {}
+        }
+
+        let result = self.fetch_profile_by_uuid(uuid).await;
+        match &result {
+            Ok(response) => self.cache_profile(&response.player_profile),
+            Err(err) if err.is_user_not_found() => self.profiles_by_uuid.insert(uuid, None),
+            Err(_) => {}
+        }
+
+        result
     }
 
     pub async fn get_profile_by_username(
@@ -88,19 +132,29 @@ impl Client {
         username: impl Into<String>,
     ) -> Result<response::get_profile_by_username::GetProfileByUsername, error::Error> {
         let username = username.into();
+        let key = username.to_lowercase();
 
-        let response = self
-            .send_safely_request(request::Request {
-                id: Uuid::new_v4(),
-                body: request::any::Kind::GetProfileByUsername(
-                    request::get_profile_by_username::GetProfileByUsername {
-                        username: username.clone(),
-                    },
-                ),
-            })
-            .await?;
+        let cached = self.profiles_by_username.get(&key);
+        self.record_cache_lookup("profile_by_username", cached.is_some());
 
-        extract_response!(response, response::any::Kind::GetProfileByUsername)
+        match cached {
+            Some(Some(player_profile)) => {
+                return Ok(response::get_profile_by_username::GetProfileByUsername {
+                    player_profile,
+                });
+            }
+            Some(None) => return Err(error::Error::user_not_found()),
+            None => {}
+        }
+
+        let result = self.fetch_profile_by_username(username).await;
+        match &result {
+            Ok(response) => self.cache_profile(&response.player_profile),
+            Err(err) if err.is_user_not_found() => self.profiles_by_username.insert(key, None),
+            Err(_) => {}
+        }
+
+        result
     }
 
     pub async fn batch_profiles_by_usernames(
@@ -109,25 +163,138 @@ impl Client {
     ) -> Result<response::batch_profiles_by_usernames::BatchProfilesByUsernames, error::Error> {
         let usernames = usernames
             .into_iter()
-            .map(|username| request::batch_profiles_by_usernames::Entry {
-                username: username.into(),
+            .map(|username| username.into())
+            .collect::<Vec<String>>();
+
+        let mut profiles = usernames
+            .iter()
+            .map(|username| {
+                let cached = self.profiles_by_username.get(&username.to_lowercase());
+                self.record_cache_lookup("profile_by_username", cached.is_some());
+
+                cached
             })
             .collect::<Vec<_>>();
 
+        let missing = profiles
+            .iter()
+            .enumerate()
+            .filter(|(_, profile)| profile.is_none())
+            .map(|(index, _)| index)
+            .collect::<Vec<_>>();
+
+        if !missing.is_empty() {
+            let fetched = self
+                .fetch_batch_profiles_by_usernames(
+                    missing
+                        .iter()
+                        .map(|&index| usernames[index].clone())
+                        .collect(),
+                )
+                .await?;
+
+            let mut fetched = fetched.player_profiles.into_iter();
+            for index in missing {
+                let profile = fetched.next().flatten();
+
+                match &profile {
+                    Some(profile) => self.cache_profile(profile),
+                    None => self
+                        .profiles_by_username
+                        .insert(usernames[index].to_lowercase(), None),
+                }
+
+                profiles[index] = Some(profile);
+            }
+        }
+
+        Ok(
+            response::batch_profiles_by_usernames::BatchProfilesByUsernames {
+                player_profiles: profiles.into_iter().map(Option::flatten).collect(),
+            },
+        )
+    }
+
+    fn record_cache_lookup(&self, cache: &str, hit: bool) {
+        let result = match hit {
+            true => "hit",
+            false => "miss",
+        };
+
+        metrics::get()
+            .cache_lookups
+            .with_label_values(&[self.socket.name(), cache, result])
+            .inc();
+    }
+
+    fn cache_profile(&self, profile: &Profile) {
+        self.profiles_by_uuid
+            .insert(profile.uuid, Some(profile.clone()));
+        self.profiles_by_username
+            .insert(profile.username.to_lowercase(), Some(profile.clone()));
+    }
+
+    async fn fetch_profile_by_uuid(
+        &self,
+        uuid: Uuid,
+    ) -> Result<response::get_profile_by_uuid::GetProfileByUuid, error::Error> {
         let response = self
-            .send_safely_request(request::Request {
-                id: Uuid::new_v4(),
-                body: request::any::Kind::BatchProfilesByUsernames(
-                    request::batch_profiles_by_usernames::BatchProfilesByUsernames {
-                        list: usernames,
-                    },
-                ),
-            })
+            .send_coalesced_request(request::any::Kind::GetProfileByUuid(
+                request::get_profile_by_uuid::GetProfileByUuid { uuid },
+            ))
+            .await?;
+
+        extract_response!(response, response::any::Kind::GetProfileByUuid)
+    }
+
+    async fn fetch_profile_by_username(
+        &self,
+        username: String,
+    ) -> Result<response::get_profile_by_username::GetProfileByUsername, error::Error> {
+        let response = self
+            .send_coalesced_request(request::any::Kind::GetProfileByUsername(
+                request::get_profile_by_username::GetProfileByUsername { username },
+            ))
+            .await?;
+
+        extract_response!(response, response::any::Kind::GetProfileByUsername)
+    }
+
+    async fn fetch_batch_profiles_by_usernames(
+        &self,
+        usernames: Vec<String>,
+    ) -> Result<response::batch_profiles_by_usernames::BatchProfilesByUsernames, error::Error> {
+        let usernames = usernames
+            .into_iter()
+            .map(|username| request::batch_profiles_by_usernames::Entry { username })
+            .collect::<Vec<_>>();
+
+        let response = self
+            .send_coalesced_request(request::any::Kind::BatchProfilesByUsernames(
+                request::batch_profiles_by_usernames::BatchProfilesByUsernames { list: usernames },
+            ))
             .await?;
 
         extract_response!(response, response::any::Kind::BatchProfilesByUsernames)
     }
 
+    /// Sends a request, sharing the response with concurrent callers of an identical request.
+    async fn send_coalesced_request(
+        &self,
+        body: request::any::Kind,
+    ) -> Result<response::any::Kind, error::Error> {
+        let key = serde_json::to_string(&body).map_err(|_| socket::Error::Internal)?;
+
+        self.in_flight
+            .run(key, || {
+                self.send_safely_request(request::Request {
+                    id: Uuid::new_v4(),
+                    body,
+                })
+            })
+            .await
+    }
+
     async fn send_safely_request(
         &self,
         request: request::any::Any,
@@ -178,6 +345,8 @@ impl Client {
                 request::Request {
                     id: Uuid::new_v4(),
                     body: request::any::Kind::RestoreToken(request::restore_token::RestoreToken {
+                        auth_id: None,
+                        access_token: None,
                         extended: HashMap::from([(pair.name, pair.value)]),
                         need_user_info: user_info,
                     }),
@@ -189,6 +358,28 @@ impl Client {
         extract_response!(response, response::any::Kind::RestoreToken)
     }
 
+    /// Checks the server token, LaunchServer lists it in `invalid_tokens` if rejected.
+    pub async fn check_token(&self) -> Result<response::restore_token::RestoreToken, error::Error> {
+        self.restore_token(
+            request::restore_token::Pair {
+                name: "checkServer".to_string(),
+                value: self.token.clone(),
+            },
+            false,
+        )
+        .await
+    }
+
+    /// Opens a dedicated connection for requests acting on a user session, see [`session::Session`].
+    pub async fn open_session(&self) -> Result<session::Session, error::Error> {
+        session::Session::open(&self.addr, self.socket.name(), &self.token, self.timeout).await
+    }
+
+    /// Returns the status of the connection to LaunchServer.
+    pub fn status(&self) -> socket::Status {
+        self.socket.status()
+    }
+
     pub async fn shutdown(&self) {
         self.socket.shutdown().await;
     }
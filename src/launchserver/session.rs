@@ -0,0 +1,180 @@
+use crate::launchserver::{
+    error,
+    socket,
+    types::{request, response},
+};
+use futures_util::{SinkExt, StreamExt};
+use std::{collections::HashMap, time::Duration};
+use tokio::{net::TcpStream, time};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite};
+use uuid::Uuid;
+
+/// Dedicated connection to LaunchServer for requests acting on a user session.
+///
+/// LaunchServer keeps the session per connection, so user logins and logouts are never sent
+/// over the shared socket, where they would replace each other and the checkServer session
+/// of the proxy. The connection is meant to serve a single HTTP request and then be closed.
+pub struct Session {
+    stream: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    /// Name identifying the server in metrics.
+    name: String,
+    token: String,
+    timeout: Duration,
+}
+
+impl Session {
+    /// Connects to LaunchServer, failing if the connection is not established in `timeout`.
+    pub async fn open(
+        addr: &url::Url,
+        name: impl Into<String>,
+        token: impl Into<String>,
+        timeout: Duration,
+    ) -> Result<Session, error::Error> {
+        let (stream, _) = time::timeout(timeout, tokio_tungstenite::connect_async(addr.as_str()))
+            .await
+            .map_err(|_| socket::Error::Disconnected)?
+            .map_err(|_| socket::Error::Disconnected)?;
+
+        Ok(Session {
+            stream,
+            name: name.into(),
+            token: token.into(),
+            timeout,
+        })
+    }
+
+    pub async fn authenticate(
+        &mut self,
+        auth_id: impl Into<String>,
+        login: impl Into<String>,
+        password: impl Into<String>,
+    ) -> Result<response::auth::Auth, error::Error> {
+        let response = self
+            .send_safely_request(request::any::Kind::Auth(request::auth::Auth {
+                login: login.into(),
+                password: request::auth::Password::Plain {
+                    password: password.into(),
+                },
+                auth_id: auth_id.into(),
+                get_session: false,
+                auth_type: request::auth::AuthType::Api,
+            }))
+            .await?;
+
+        extract_response!(response, response::any::Kind::Auth)
+    }
+
+    pub async fn restore_session(
+        &mut self,
+        auth_id: impl Into<String>,
+        access_token: impl Into<String>,
+        user_info: bool,
+    ) -> Result<response::restore_token::RestoreToken, error::Error> {
+        let response = self
+            .send_safely_request(request::any::Kind::RestoreToken(
+                request::restore_token::RestoreToken {
+                    auth_id: Some(auth_id.into()),
+                    access_token: Some(access_token.into()),
+                    extended: HashMap::new(),
+                    need_user_info: user_info,
+                },
+            ))
+            .await?;
+
+        extract_response!(response, response::any::Kind::RestoreToken)
+    }
+
+    /// Ends the session held by this connection, or every session of its user if `exit_all`.
+    pub async fn exit(&mut self, exit_all: bool) -> Result<response::exit::Exit, error::Error> {
+        let response = self
+            .send_safely_request(request::any::Kind::Exit(request::exit::Exit { exit_all }))
+            .await?;
+
+        extract_response!(response, response::any::Kind::Exit)
+    }
+
+    /// Closes the connection, dropping whatever session LaunchServer still holds for it.
+    pub async fn close(mut self) {
+        let _ = time::timeout(self.timeout, self.stream.close(None)).await;
+    }
+
+    /// Sends a request, authorizing the connection with the server token if LaunchServer
+    /// requires it.
+    async fn send_safely_request(
+        &mut self,
+        body: request::any::Kind,
+    ) -> Result<response::any::Kind, error::Error> {
+        let response = self.send_request(body.clone()).await?;
+
+        let response::any::Kind::Error(response::error::Error {
+            kind: response::error::Kind::PermissionsDenied,
+        }) = response
+        else {
+            return Ok(response);
+        };
+
+        let restored = self
+            .send_request(request::any::Kind::RestoreToken(
+                request::restore_token::RestoreToken {
+                    auth_id: None,
+                    access_token: None,
+                    extended: HashMap::from([("checkServer".to_string(), self.token.clone())]),
+                    need_user_info: false,
+                },
+            ))
+            .await?;
+
+        match restored {
+            response::any::Kind::RestoreToken(restored) if restored.invalid_tokens.is_empty() => {
+                Ok(self.send_request(body).await?)
+            }
+            _ => Err(error::Error::UnexpectedResponse(response)),
+        }
+    }
+
+    async fn send_request(
+        &mut self,
+        body: request::any::Kind,
+    ) -> Result<response::any::Kind, socket::Error> {
+        let request = request::Request {
+            id: Uuid::new_v4(),
+            body,
+        };
+        let kind = request.body.name();
+        let request_id = request.id;
+        let name = self.name.clone();
+        let timeout = self.timeout;
+
+        socket::observe_request(&name, kind, request_id, async {
+            let json_request =
+                serde_json::to_string(&request).map_err(|_| socket::Error::Internal)?;
+
+            time::timeout(timeout, async {
+                self.stream
+                    .send(tungstenite::Message::text(json_request))
+                    .await
+                    .map_err(|_| socket::Error::Disconnected)?;
+
+                // Messages unrelated to the request, such as notifications, are skipped.
+                loop {
+                    let message = match self.stream.next().await {
+                        Some(Ok(message)) => message,
+                        Some(Err(_)) | None => return Err(socket::Error::Disconnected),
+                    };
+
+                    let tungstenite::Message::Text(text) = message else {
+                        continue;
+                    };
+
+                    match serde_json::from_str::<response::any::Any>(&text) {
+                        Ok(response) if response.id == request_id => return Ok(response.body),
+                        _ => continue,
+                    }
+                }
+            })
+            .await
+            .unwrap_or(Err(socket::Error::ResponseNotReceived))
+        })
+        .await
+    }
+}
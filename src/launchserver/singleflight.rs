@@ -0,0 +1,211 @@
+use std::{collections::HashMap, hash::Hash, sync::Mutex};
+use tokio::sync::oneshot;
+
+/// Coalesces concurrent calls with the same key into a single execution.
+///
+/// The first caller (the leader) runs the call, callers arriving while it is in flight wait
+/// for the leader's result. If the leader is cancelled, waiters run the call themselves.
+pub struct SingleFlight<K, V> {
+    in_flight: Mutex<HashMap<K, Vec<oneshot::Sender<V>>>>,
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> SingleFlight<K, V> {
+    pub fn new() -> SingleFlight<K, V> {
+        SingleFlight {
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub async fn run<F: Future<Output = V>>(&self, key: K, call: impl FnOnce() -> F) -> V {
+        let waiter = {
+            let mut in_flight = self.in_flight.lock().unwrap();
+
+            match in_flight.get_mut(&key) {
+                Some(waiters) => {
+                    let (tx, rx) = oneshot::channel();
+                    waiters.push(tx);
+                    Some(rx)
+                }
+                None => {
+                    in_flight.insert(key.clone(), Vec::new());
+                    None
+                }
+            }
+        };
+
+        if let Some(rx) = waiter {
+            return match rx.await {
+                Ok(value) => value,
+                Err(_) => call().await,
+            };
+        }
+
+        let leader = Leader {
+            flight: self,
+            key: Some(key),
+        };
+        let value = call().await;
+
+        for waiter in leader.complete() {
+            let _ = waiter.send(value.clone());
+        }
+
+        value
+    }
+}
+
+impl<K: Eq + Hash + Clone, V: Clone> Default for SingleFlight<K, V> {
+    fn default() -> Self {
+        SingleFlight::new()
+    }
+}
+
+/// Unregisters the in-flight call when the leader finishes or is dropped mid-flight.
+struct Leader<'a, K: Eq + Hash, V> {
+    flight: &'a SingleFlight<K, V>,
+    key: Option<K>,
+}
+
+impl<K: Eq + Hash, V> Leader<'_, K, V> {
+    fn complete(mut self) -> Vec<oneshot::Sender<V>> {
+        self.take_waiters()
+    }
+
+    fn take_waiters(&mut self) -> Vec<oneshot::Sender<V>> {
+        let Some(key) = self.key.take() else {
+            return Vec::new();
+        };
+
+        self.flight
+            .in_flight
+            .lock()
+            .unwrap()
+            .remove(&key)
+            .unwrap_or_default()
+    }
+}
+
+impl<K: Eq + Hash, V> Drop for Leader<'_, K, V> {
+    fn drop(&mut self) {
+        // Dropping the senders wakes up the waiters so they can retry on their own.
+        self.take_waiters();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        future,
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+        time::Duration,
+    };
+    use tokio::{
+        sync::{Barrier, watch},
+        task,
+        time,
+    };
+
+    const FAIL_FAST: Duration = Duration::from_secs(2);
+
+    /// Waits until `count` callers wait for the in-flight call of `key`.
+    async fn wait_for_waiters(flight: &SingleFlight<&'static str, u32>, key: &str, count: usize) {
+        time::timeout(FAIL_FAST, async {
+            while flight.in_flight.lock().unwrap().get(key).map(Vec::len) != Some(count) {
+                task::yield_now().await;
+            }
+        })
+        .await
+        .expect("callers did not join the in-flight call");
+    }
+
+    #[tokio::test]
+    async fn coalesces_concurrent_calls_with_same_key() {
+        let flight = Arc::new(SingleFlight::new());
+        let calls = Arc::new(AtomicUsize::new(0));
+        let (release, released) = watch::channel(false);
+
+        let callers = (0..5)
+            .map(|_| {
+                let flight = Arc::clone(&flight);
+                let calls = Arc::clone(&calls);
+                let mut released = released.clone();
+
+                tokio::spawn(async move {
+                    flight
+                        .run("key", || async move {
+                            calls.fetch_add(1, Ordering::SeqCst);
+                            let _ = released.wait_for(|released| *released).await;
+                            42
+                        })
+                        .await
+                })
+            })
+            .collect::<Vec<_>>();
+
+        wait_for_waiters(&flight, "key", 4).await;
+        release.send(true).unwrap();
+
+        for caller in callers {
+            assert_eq!(caller.await.unwrap(), 42);
+        }
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(flight.in_flight.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn runs_calls_with_different_keys_separately() {
+        let flight = SingleFlight::new();
+        // Both calls must be in flight at once to pass the barrier.
+        let barrier = Barrier::new(2);
+
+        let (a, b) = time::timeout(FAIL_FAST, async {
+            tokio::join!(
+                flight.run("a", || async {
+                    barrier.wait().await;
+                    1
+                }),
+                flight.run("b", || async {
+                    barrier.wait().await;
+                    2
+                }),
+            )
+        })
+        .await
+        .expect("calls with different keys were coalesced");
+
+        assert_eq!((a, b), (1, 2));
+    }
+
+    #[tokio::test]
+    async fn waiters_retry_when_leader_is_dropped() {
+        let flight = Arc::new(SingleFlight::new());
+
+        let leader = tokio::spawn({
+            let flight = Arc::clone(&flight);
+            async move { flight.run("key", future::pending::<u32>).await }
+        });
+        wait_for_waiters(&flight, "key", 0).await;
+
+        let waiters = (0..2)
+            .map(|_| {
+                let flight = Arc::clone(&flight);
+                tokio::spawn(async move { flight.run("key", || async { 7 }).await })
+            })
+            .collect::<Vec<_>>();
+        wait_for_waiters(&flight, "key", 2).await;
+
+        leader.abort();
+
+        for waiter in waiters {
+            let value = time::timeout(FAIL_FAST, waiter)
+                .await
+                .expect("waiter hangs after the leader was dropped");
+            assert_eq!(value.unwrap(), 7);
+        }
+        assert!(flight.in_flight.lock().unwrap().is_empty());
+    }
+}
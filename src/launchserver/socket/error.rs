@@ -1,8 +1,9 @@
 use std::fmt::{Display, Formatter};
 
-#[derive(Debug)]
+#[derive(Clone, Debug)]
 pub enum Error {
     ResponseNotReceived,
+    Disconnected,
     Internal,
 }
 
@@ -12,6 +13,7 @@ impl Display for Error {
     fn fmt(&self, fmt: &mut Formatter<'_>) -> std::fmt::Result {
         match self {
             Error::ResponseNotReceived => write!(fmt, "response not received"),
+            Error::Disconnected => write!(fmt, "socket is disconnected"),
             Error::Internal => write!(fmt, "internal error occurred"),
         }
     }
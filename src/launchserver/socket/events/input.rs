@@ -8,13 +8,13 @@ pub enum Loop {
 }
 
 pub mod loopback {
-    use std::time::Duration;
+    use crate::launchserver::socket::ReconnectPolicy;
     use tokio::sync::oneshot;
 
     pub enum Loop {
         ConnectSocket {
             addr: url::Url,
-            timeout: Option<Duration>,
+            policy: ReconnectPolicy,
         },
         Shutdown(oneshot::Sender<()>),
     }
@@ -1,6 +1,7 @@
 pub mod loopback {
     pub enum Loop<R, W> {
         SocketConnected { read: R, write: W },
+        GaveUp,
     }
 }
 
@@ -25,6 +26,8 @@ pub mod websocket {
 
         pub enum Loop {
             Message(tungstenite::Message),
+            /// The stream ended or failed, no more messages will arrive.
+            Closed,
         }
     }
 }
@@ -1,37 +1,45 @@
 mod error;
 mod events;
 mod options;
+mod status;
 
 pub use error::*;
 pub use options::*;
-
-use crate::launchserver::{
-    socket::events::{input, output},
-    types::{request, response},
+pub use status::*;
+
+use crate::{
+    launchserver::{
+        socket::events::{input, output},
+        types::{request, response},
+    },
+    metrics,
 };
 use futures_util::{
     SinkExt,
     StreamExt,
     stream::{SplitSink, SplitStream},
 };
-use std::{collections::HashMap, time::Duration};
+use std::{
+    collections::{HashMap, VecDeque},
+    time::{Duration, SystemTime},
+};
 use tokio::{
     net::TcpStream,
-    sync::{mpsc, oneshot},
+    sync::{mpsc, oneshot, watch},
     task,
     time,
 };
 use tokio_tungstenite::{MaybeTlsStream, WebSocketStream, tungstenite};
-use tracing::debug;
+use tracing::{Instrument, debug, error, info, info_span, warn};
 use uuid::Uuid;
 
-const CAPACITY: usize = 256;
-const CONCURRENCY: usize = 5120;
+/// Sender for delivering a response, or the reason it will never arrive, to the caller.
+type ResponseSender = oneshot::Sender<Result<response::any::Kind, Error>>;
 
 /// Represents a message sent to the actor.
 struct ActorMessage {
     /// Sender for sending back the response.
-    sender: oneshot::Sender<response::any::Kind>,
+    sender: ResponseSender,
     /// The actual request to be processed.
     request: request::any::Any,
 }
@@ -44,6 +52,10 @@ pub struct Socket {
     /// Sender to communicate with the actor handling the loop.
     actor_sender: mpsc::Sender<input::Loop>,
     actor_handle: task::JoinHandle<()>,
+    /// Connection status published by the actor loop.
+    status: watch::Receiver<status::Status>,
+    /// Name identifying the socket in metrics.
+    name: String,
 
     options: options::SocketOptions,
 }
@@ -59,21 +71,39 @@ impl Socket {
     ///
     /// A new instance of `Socket`.
     pub fn new(addr: impl Into<url::Url>, options: options::SocketOptions) -> Socket {
-        let (actor_sender, actor_receiver) = mpsc::channel(CAPACITY);
+        let addr = addr.into();
+        let name = options.name.clone().unwrap_or_else(|| addr.to_string());
+
+        let (actor_sender, actor_receiver) = mpsc::channel(options.capacity);
+        let (status_sender, status) = watch::channel(status::Status::default());
         let actor_handle = tokio::spawn(start_handle_loop(
-            addr.into(),
-            options.reconnection_timeout,
+            addr,
+            name.clone(),
+            options.clone(),
             actor_receiver,
+            status_sender,
         ));
 
         Socket {
             actor_sender,
             actor_handle,
+            status,
+            name,
 
             options,
         }
     }
 
+    /// Returns the name identifying the socket in metrics.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the current connection status.
+    pub fn status(&self) -> status::Status {
+        self.status.borrow().clone()
+    }
+
     /// Sends a request through the WebSocket and awaits a response.
     ///
     /// # Arguments
@@ -87,6 +117,23 @@ impl Socket {
         &self,
         request: request::any::Any,
         timeout: Duration,
+    ) -> Result<response::any::Kind, Error> {
+        let kind = request.body.name();
+        let request_id = request.id;
+
+        observe_request(
+            &self.name,
+            kind,
+            request_id,
+            self.send_request_inner(request, timeout),
+        )
+        .await
+    }
+
+    async fn send_request_inner(
+        &self,
+        request: request::any::Any,
+        timeout: Duration,
     ) -> Result<response::any::Kind, Error> {
         let request_id = request.id;
 
@@ -101,7 +148,7 @@ impl Socket {
             .expect("actor channel is closed");
 
         match time::timeout(timeout, rx).await {
-            Ok(res) => Ok(res.expect("sender of the callback cannot be closed")),
+            Ok(res) => res.unwrap_or(Err(Error::Internal)),
             Err(_) => {
                 let _ = self
                     .actor_sender
@@ -125,30 +172,80 @@ impl Socket {
     }
 }
 
+/// Runs a request in a span, recording its outcome and latency under the socket `name`.
+pub(crate) async fn observe_request(
+    name: &str,
+    kind: &'static str,
+    request_id: Uuid,
+    request: impl Future<Output = Result<response::any::Kind, Error>>,
+) -> Result<response::any::Kind, Error> {
+    let span = info_span!(
+        "launchserver_request",
+        server_id = %name,
+        kind,
+        request_id = %request_id
+    );
+
+    let started_at = time::Instant::now();
+    let result = request.instrument(span.clone()).await;
+
+    let outcome = match &result {
+        Ok(response::any::Kind::Error(_)) => "rejected",
+        Ok(_) => "ok",
+        Err(Error::ResponseNotReceived) => "timeout",
+        Err(Error::Disconnected) => "disconnected",
+        Err(Error::Internal) => "error",
+    };
+
+    let metrics = metrics::get();
+    metrics
+        .launchserver_requests
+        .with_label_values(&[name, kind, outcome])
+        .inc();
+    metrics
+        .launchserver_request_duration
+        .with_label_values(&[name, kind])
+        .observe(started_at.elapsed().as_secs_f64());
+
+    span.in_scope(|| {
+        debug!(
+            outcome,
+            elapsed_ms = started_at.elapsed().as_millis() as u64,
+            "request completed"
+        )
+    });
+
+    result
+}
+
 /// Starts the main loop that manages the WebSocket connection and message handling.
 ///
 /// # Arguments
 ///
 /// * `addr` - The URL of the WebSocket server to connect to.
-/// * `reconnection_timeout` - Optional duration to wait before attempting reconnection.
+/// * `name` - Name identifying the socket in metrics.
+/// * `options` - Socket options, including the reconnection delay and channel sizes.
 /// * `ev_receiver` - Receiver for incoming events from the actor.
+/// * `status_sender` - Sender publishing connection status changes.
 async fn start_handle_loop(
     addr: impl Into<url::Url>,
-    reconnection_timeout: impl Into<Option<Duration>>,
+    name: String,
+    options: options::SocketOptions,
     mut ev_receiver: mpsc::Receiver<input::Loop>,
+    status_sender: watch::Sender<status::Status>,
 ) {
     let addr = addr.into();
-    let reconnection_timeout = reconnection_timeout.into();
 
     // Map to correlate requests with their response senders using UUIDs.
-    let mut requests_callbacks: HashMap<Uuid, oneshot::Sender<response::any::Kind>> =
-        HashMap::new();
+    let mut requests_callbacks: HashMap<Uuid, ResponseSender> = HashMap::new();
+
+    // Requests waiting for a reconnection, ordered by their deadline.
+    let mut pending_requests: VecDeque<(time::Instant, ActorMessage)> = VecDeque::new();
 
-    // Channels for WebSocket input and output events.
-    let (mut ws_input_ev_sender, mut ws_input_ev_receiver) =
-        mpsc::channel::<input::websocket::Loop>(CAPACITY);
-    let (mut ws_output_ev_sender, mut ws_output_ev_receiver) =
-        mpsc::channel::<output::websocket::Loop>(CAPACITY);
+    // Channels for WebSocket input and output events. The opposite ends are dropped
+    // until the first connection is established.
+    let (mut ws_input_ev_sender, _) = mpsc::channel::<input::websocket::Loop>(options.capacity);
+    let (_, mut ws_output_ev_receiver) = mpsc::channel::<output::websocket::Loop>(options.capacity);
 
     // Channels for loopback (connection management) events.
     let (loopback_input_ev_sender, loopback_input_ev_receiver) =
@@ -158,12 +255,13 @@ async fn start_handle_loop(
 
     // Flags to track connection state.
     let mut ws_is_connected = false;
+    let mut ws_is_connecting = true;
 
     // Initiate connection to the WebSocket server.
     let _ = loopback_input_ev_sender
         .send(input::loopback::Loop::ConnectSocket {
             addr: addr.clone(),
-            timeout: reconnection_timeout,
+            policy: options.reconnect.clone(),
         })
         .await;
 
@@ -171,20 +269,32 @@ async fn start_handle_loop(
     tokio::spawn(start_loopback_handle_loop(
         loopback_output_ev_sender,
         loopback_input_ev_receiver,
+        status_sender.clone(),
     ));
 
+    let pending_callbacks = metrics::get()
+        .launchserver_pending_callbacks
+        .with_label_values(&[name.as_str()]);
+    let reconnects = metrics::get()
+        .launchserver_reconnects
+        .with_label_values(&[name.as_str()]);
+
     // Main event loop.
     loop {
+        pending_callbacks.set((requests_callbacks.len() + pending_requests.len()) as i64);
+
         tokio::select! {
             // Handle events from the loopback output (e.g., successful connection)
             Some(event) = loopback_output_ev_receiver.recv() => {
                 match event {
                     output::loopback::Loop::SocketConnected { read, write } => {
                         // Reinitialize WebSocket input and output channels upon connection.
+                        let ws_input_ev_receiver;
+                        let ws_output_ev_sender;
                         (ws_input_ev_sender, ws_input_ev_receiver) =
-                            mpsc::channel::<input::websocket::Loop>(CAPACITY);
+                            mpsc::channel::<input::websocket::Loop>(options.capacity);
                         (ws_output_ev_sender, ws_output_ev_receiver) =
-                            mpsc::channel::<output::websocket::Loop>(CAPACITY);
+                            mpsc::channel::<output::websocket::Loop>(options.capacity);
 
                         // Spawn the WebSocket handler loop.
                         tokio::spawn(start_ws_handle_loop(
@@ -192,9 +302,26 @@ async fn start_handle_loop(
                             ws_input_ev_receiver,
                             read,
                             write,
+                            options.clone(),
                         ));
 
                         ws_is_connected = true;
+                        ws_is_connecting = false;
+                        status_sender.send_modify(|status| {
+                            status.state = status::ConnectionState::Connected;
+                            status.last_connected_at = Some(SystemTime::now());
+                        });
+
+                        // Send the requests queued while the socket was reconnecting.
+                        for (_, msg) in pending_requests.drain(..) {
+                            forward_request(msg, &mut requests_callbacks, &ws_input_ev_sender).await;
+                        }
+                    }
+                    output::loopback::Loop::GaveUp => {
+                        ws_is_connecting = false;
+                        status_sender.send_modify(|status| status.state = status::ConnectionState::Disconnected);
+
+                        fail_requests(pending_requests.drain(..).map(|(_, msg)| msg.sender));
                     }
                 }
             }
@@ -202,18 +329,38 @@ async fn start_handle_loop(
             Some(event) = ev_receiver.recv() => {
                 match event {
                     input::Loop::Message(msg) => {
-                        let Ok(json_request) = serde_json::to_string(&msg.request) else {
+                        if ws_is_connected {
+                            forward_request(msg, &mut requests_callbacks, &ws_input_ev_sender).await;
                             continue;
-                        };
+                        }
 
-                        requests_callbacks.insert(msg.request.id, msg.sender);
+                        // Reconnecting was given up earlier, a new request restarts it.
+                        if !ws_is_connected && !ws_is_connecting {
+                            info!("restarting connection attempts to socket {}", addr);
 
-                        let _ = ws_input_ev_sender
-                            .send(input::websocket::Loop::Message(tungstenite::Message::text(json_request)))
-                            .await;
+                            ws_is_connecting = true;
+                            status_sender.send_modify(|status| status.state = status::ConnectionState::Connecting);
+
+                            let _ = loopback_input_ev_sender
+                                .send(input::loopback::Loop::ConnectSocket {
+                                    addr: addr.clone(),
+                                    policy: options.reconnect.clone(),
+                                })
+                                .await;
+                        }
+
+                        match options.reconnect_wait {
+                            Some(wait) => pending_requests.push_back((time::Instant::now() + wait, msg)),
+                            None => fail_requests([msg.sender]),
+                        }
                     },
                     input::Loop::CancelMessage(request_id) => {
-                        if requests_callbacks.remove(&request_id).is_none() {
+                        let pending_len = pending_requests.len();
+                        pending_requests.retain(|(_, msg)| msg.request.id != request_id);
+
+                        if requests_callbacks.remove(&request_id).is_none()
+                            && pending_requests.len() == pending_len
+                        {
                             debug!("failed to remove message {} with exceeded timeout", request_id);
                         }
                     }
@@ -236,6 +383,11 @@ async fn start_handle_loop(
                             .await;
                         let _ = loopback_rx.await;
 
+                        fail_requests(requests_callbacks.drain().map(|(_, sender)| sender));
+                        fail_requests(pending_requests.drain(..).map(|(_, msg)| msg.sender));
+
+                        status_sender.send_modify(|status| status.state = status::ConnectionState::Disconnected);
+
                         // Confirm shutdown to the caller.
                         let _ = sender.send(());
 
@@ -244,6 +396,21 @@ async fn start_handle_loop(
                 }
             }
 
+            // Fail requests that waited for a reconnection for too long.
+            _ = time::sleep_until(
+                pending_requests
+                    .front()
+                    .map_or_else(time::Instant::now, |(deadline, _)| *deadline)
+            ), if !pending_requests.is_empty() => {
+                let now = time::Instant::now();
+
+                while pending_requests.front().is_some_and(|(deadline, _)| *deadline <= now) {
+                    if let Some((_, msg)) = pending_requests.pop_front() {
+                        fail_requests([msg.sender]);
+                    }
+                }
+            }
+
             // Handle events from the WebSocket output (e.g., incoming messages, errors).
             Some(event) = ws_output_ev_receiver.recv() => {
                 match event {
@@ -266,12 +433,23 @@ async fn start_handle_loop(
                         };
 
                         // Send the response back through the oneshot channel.
-                        if sender.send(response.body).is_err() {
+                        if sender.send(Ok(response.body)).is_err() {
                             debug!("failed to send response to channel");
                         }
                     }
                     output::websocket::Loop::Disconnect => {
+                        warn!("socket {} disconnected, reconnecting", addr);
+                        reconnects.inc();
+
                         ws_is_connected = false;
+                        ws_is_connecting = true;
+                        status_sender.send_modify(|status| {
+                            status.state = status::ConnectionState::Connecting;
+                            status.last_error = Some("connection lost".to_string());
+                        });
+
+                        // Responses to requests sent over the lost connection will never arrive.
+                        fail_requests(requests_callbacks.drain().map(|(_, sender)| sender));
 
                         // Initiate shutdown of the WebSocket handler.
                         let (ws_tx, ws_rx) = oneshot::channel();
@@ -284,7 +462,7 @@ async fn start_handle_loop(
                         let _ = loopback_input_ev_sender
                         .send(input::loopback::Loop::ConnectSocket {
                             addr: addr.clone(),
-                            timeout: reconnection_timeout
+                            policy: options.reconnect.clone(),
                         })
                         .await;
                     }
@@ -294,42 +472,101 @@ async fn start_handle_loop(
     }
 }
 
+/// Registers the request callback and passes the serialized request to the WebSocket handler.
+///
+/// # Arguments
+///
+/// * `msg` - The request and its response sender.
+/// * `requests_callbacks` - Map correlating requests with their response senders.
+/// * `ws_sender` - Sender to the WebSocket handler loop.
+async fn forward_request(
+    msg: ActorMessage,
+    requests_callbacks: &mut HashMap<Uuid, ResponseSender>,
+    ws_sender: &mpsc::Sender<input::websocket::Loop>,
+) {
+    let Ok(json_request) = serde_json::to_string(&msg.request) else {
+        let _ = msg.sender.send(Err(Error::Internal));
+        return;
+    };
+
+    requests_callbacks.insert(msg.request.id, msg.sender);
+
+    let _ = ws_sender
+        .send(input::websocket::Loop::Message(tungstenite::Message::text(
+            json_request,
+        )))
+        .await;
+}
+
+/// Fails the given requests with [`Error::Disconnected`].
+fn fail_requests(senders: impl IntoIterator<Item = ResponseSender>) {
+    for sender in senders {
+        let _ = sender.send(Err(Error::Disconnected));
+    }
+}
+
 /// Handles the loopback for managing socket connections, including reconnections.
 ///
 /// # Arguments
 ///
 /// * `ev_sender` - Sender to communicate events back to the main loop.
 /// * `ev_receiver` - Receiver for incoming loopback events.
+/// * `status_sender` - Sender publishing connection failures.
 async fn start_loopback_handle_loop(
     ev_sender: mpsc::Sender<output::loopback::Loop<WebSocketReceiver, WebSocketSender>>,
     mut ev_receiver: mpsc::Receiver<input::loopback::Loop>,
+    status_sender: watch::Sender<status::Status>,
 ) {
     let mut connect_task: Option<task::JoinHandle<()>> = None;
 
     while let Some(event) = ev_receiver.recv().await {
         match event {
-            input::loopback::Loop::ConnectSocket { addr, timeout } => {
+            input::loopback::Loop::ConnectSocket { addr, policy } => {
                 if let Some(task) = connect_task {
                     task.abort();
                 }
 
                 connect_task = Some(tokio::spawn({
                     let ev_sender = ev_sender.clone();
+                    let status_sender = status_sender.clone();
 
                     async move {
+                        let mut failed_attempts = 0;
+
                         let (ws_stream, _) = loop {
                             match tokio_tungstenite::connect_async(addr.clone()).await {
                                 Ok(ws_stream) => {
-                                    debug!("successfully connected to socket with addr: {}", addr);
+                                    info!(
+                                        "connected to socket {} after {} failed attempt(s)",
+                                        addr, failed_attempts
+                                    );
 
                                     break ws_stream;
                                 }
                                 Err(err) => {
-                                    debug!("error with connect to socket: {}", err);
-
-                                    if let Some(timeout) = timeout {
-                                        tokio::time::sleep(timeout).await;
+                                    failed_attempts += 1;
+                                    status_sender.send_modify(|status| {
+                                        status.last_error = Some(err.to_string());
+                                    });
+
+                                    if policy.gives_up_after(failed_attempts) {
+                                        error!(
+                                            "giving up connecting to socket {} after {} attempt(s): {}",
+                                            addr, failed_attempts, err
+                                        );
+
+                                        let _ =
+                                            ev_sender.send(output::loopback::Loop::GaveUp).await;
+                                        return;
                                     }
+
+                                    let delay = policy.delay(failed_attempts);
+                                    warn!(
+                                        "failed to connect to socket {} (attempt {}): {}, retrying in {:?}",
+                                        addr, failed_attempts, err, delay
+                                    );
+
+                                    time::sleep(delay).await;
                                 }
                             }
                         };
@@ -369,38 +606,72 @@ async fn start_loopback_handle_loop(
 /// * `ev_receiver` - Receiver for incoming WebSocket events.
 /// * `ws_receiver` - The split WebSocket receiver stream.
 /// * `ws_sender` - The split WebSocket sender sink.
+/// * `options` - Socket options, including channel sizes and concurrency limits.
 async fn start_ws_handle_loop(
     ev_sender: mpsc::Sender<output::websocket::Loop>,
     mut ev_receiver: mpsc::Receiver<input::websocket::Loop>,
     ws_receiver: WebSocketReceiver,
     ws_sender: WebSocketSender,
+    options: options::SocketOptions,
 ) {
     // Channels for handling outgoing WebSocket messages.
     let (ws_sender_input_ev_tx, ws_sender_input_ev_rx) =
-        mpsc::channel::<input::websocket::sender::Loop>(CAPACITY);
+        mpsc::channel::<input::websocket::sender::Loop>(options.capacity);
     let (ws_sender_output_ev_tx, mut ws_sender_output_ev_rx) =
-        mpsc::channel::<output::websocket::sender::Loop>(CAPACITY);
+        mpsc::channel::<output::websocket::sender::Loop>(options.capacity);
 
     // Channels for handling incoming WebSocket messages.
     let (ws_receiver_input_ev_tx, ws_receiver_input_ev_rx) =
-        mpsc::channel::<input::websocket::receiver::Loop>(CAPACITY);
+        mpsc::channel::<input::websocket::receiver::Loop>(options.capacity);
     let (ws_receiver_output_ev_tx, mut ws_receiver_output_ev_rx) =
-        mpsc::channel::<output::websocket::receiver::Loop>(CAPACITY);
+        mpsc::channel::<output::websocket::receiver::Loop>(options.capacity);
 
     // Spawn tasks to handle outgoing and incoming WebSocket messages.
     tokio::spawn(start_handle_outgoing_messages(
         ws_sender_output_ev_tx,
         ws_sender_input_ev_rx,
         ws_sender,
+        options.concurrency,
     ));
     tokio::spawn(start_handle_incoming_messages(
         ws_receiver_output_ev_tx,
         ws_receiver_input_ev_rx,
         ws_receiver,
+        options.concurrency,
     ));
 
+    // Keepalive state: pings are sent every interval and the peer must answer each in time.
+    // The interval is never polled when pings are disabled, the fallback only keeps it valid.
+    let ping_period = options
+        .ping_interval
+        .unwrap_or(options::DEFAULT_PING_INTERVAL);
+    let mut ping_interval = time::interval_at(time::Instant::now() + ping_period, ping_period);
+    let mut pong_deadline: Option<time::Instant> = None;
+    let mut is_dead = false;
+
     loop {
         tokio::select! {
+            // Send keepalive pings while the connection is considered alive.
+            _ = ping_interval.tick(), if options.ping_interval.is_some() && !is_dead => {
+                if pong_deadline.is_none() {
+                    pong_deadline = Some(time::Instant::now() + options.pong_timeout);
+                }
+
+                let _ = ws_sender_input_ev_tx
+                    .send(input::websocket::sender::Loop::Message(tungstenite::Message::Ping(Default::default())))
+                    .await;
+            }
+
+            // Treat the connection as dead once the peer misses the pong deadline.
+            _ = time::sleep_until(pong_deadline.unwrap_or_else(time::Instant::now)), if pong_deadline.is_some() => {
+                warn!("socket did not answer ping within {:?}", options.pong_timeout);
+
+                pong_deadline = None;
+                is_dead = true;
+
+                let _ = ev_sender.send(output::websocket::Loop::Disconnect).await;
+            }
+
             // Handle incoming events from the main loop.
             Some(event) = ev_receiver.recv() => {
                 match event {
@@ -446,7 +717,9 @@ async fn start_ws_handle_loop(
                                 | tungstenite::Error::Io(_)
                                 | tungstenite::Error::Tls(_)
                                 | tungstenite::Error::Protocol(_)
-                        ) {
+                        ) && !is_dead {
+                            is_dead = true;
+
                             let _ = ev_sender.send(output::websocket::Loop::Disconnect).await;
                         }
                     }
@@ -457,7 +730,32 @@ async fn start_ws_handle_loop(
             Some(ws_receiver_output_ev) = ws_receiver_output_ev_rx.recv() => {
                 match ws_receiver_output_ev {
                     output::websocket::receiver::Loop::Message(msg) => {
-                        let _ = ev_sender.send(output::websocket::Loop::Message(msg)).await;
+                        // Any frame from the peer proves the connection is alive.
+                        pong_deadline = None;
+
+                        if msg.is_pong() {
+                            continue;
+                        }
+
+                        if !msg.is_close() {
+                            let _ = ev_sender.send(output::websocket::Loop::Message(msg)).await;
+                            continue;
+                        }
+
+                        debug!("socket closed by peer: {:?}", msg);
+
+                        if !is_dead {
+                            is_dead = true;
+
+                            let _ = ev_sender.send(output::websocket::Loop::Disconnect).await;
+                        }
+                    }
+                    output::websocket::receiver::Loop::Closed => {
+                        if !is_dead {
+                            is_dead = true;
+
+                            let _ = ev_sender.send(output::websocket::Loop::Disconnect).await;
+                        }
                     }
                 }
             }
@@ -472,10 +770,12 @@ async fn start_ws_handle_loop(
 /// * `ev_sender` - Sender to communicate events back to the WebSocket handler.
 /// * `ev_receiver` - Receiver for incoming outgoing message events.
 /// * `ws_sender` - The WebSocket sender sink.
+/// * `concurrency` - Maximum number of failure notifications in flight.
 async fn start_handle_outgoing_messages(
     ev_sender: mpsc::Sender<output::websocket::sender::Loop>,
     mut ev_receiver: mpsc::Receiver<input::websocket::sender::Loop>,
     mut ws_sender: WebSocketSender,
+    concurrency: usize,
 ) {
     let mut join_set = tokio::task::JoinSet::<()>::new();
 
@@ -484,7 +784,7 @@ async fn start_handle_outgoing_messages(
             Some(event) = ev_receiver.recv() => {
                 match event {
                     input::websocket::sender::Loop::Message(msg) => {
-                        while join_set.len() >= CONCURRENCY {
+                        while join_set.len() >= concurrency {
                             join_set.join_next().await;
                         }
 
@@ -522,19 +822,22 @@ async fn start_handle_outgoing_messages(
 /// * `ev_sender` - Sender to communicate events back to the WebSocket handler.
 /// * `ev_receiver` - Receiver for incoming message events.
 /// * `ws_receiver` - The WebSocket receiver stream.
+/// * `concurrency` - Maximum number of incoming messages forwarded concurrently.
 async fn start_handle_incoming_messages(
     ev_sender: mpsc::Sender<output::websocket::receiver::Loop>,
     mut ev_receiver: mpsc::Receiver<input::websocket::receiver::Loop>,
     mut ws_receiver: WebSocketReceiver,
+    concurrency: usize,
 ) {
     let mut join_set = tokio::task::JoinSet::<()>::new();
+    let mut is_closed = false;
 
     loop {
         tokio::select! {
-            Some(msg) = ws_receiver.next() => {
+            msg = ws_receiver.next(), if !is_closed => {
                 match msg {
-                    Ok(msg) => {
-                        while join_set.len() >= CONCURRENCY {
+                    Some(Ok(msg)) => {
+                        while join_set.len() >= concurrency {
                             join_set.join_next().await;
                         }
 
@@ -549,10 +852,22 @@ async fn start_handle_incoming_messages(
                             }
                         });
                     },
-                    Err(err) => {
-                        debug!("socket receiver error: {}", err)
+                    Some(Err(err)) => {
+                        debug!("socket receiver error: {}", err);
+                        is_closed = true;
+                    }
+                    None => {
+                        debug!("socket stream ended");
+                        is_closed = true;
                     }
                 }
+
+                if is_closed {
+                    // Deliver the messages received before the connection was lost first.
+                    while join_set.join_next().await.is_some() {}
+
+                    let _ = ev_sender.send(output::websocket::receiver::Loop::Closed).await;
+                }
             },
             Some(event) = ev_receiver.recv() => {
                 match event {
@@ -569,3 +884,108 @@ async fn start_handle_incoming_messages(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    const FAIL_FAST: Duration = Duration::from_secs(2);
+
+    /// Starts a WebSocket server accepting a single connection and handing it to `handle`.
+    async fn serve_once<F>(
+        handle: impl FnOnce(WebSocketStream<TcpStream>) -> F + Send + 'static,
+    ) -> url::Url
+    where
+        F: Future<Output = ()> + Send,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            handle(ws).await;
+
+            // Keep the listener open so reconnection attempts hang instead of failing.
+            std::future::pending::<()>().await;
+        });
+
+        format!("ws://{addr}").parse().unwrap()
+    }
+
+    fn socket(addr: url::Url) -> Socket {
+        Socket::new(
+            addr,
+            options::SocketOptions::builder()
+                .with_timeout(Duration::from_secs(30))
+                .build(),
+        )
+    }
+
+    fn request() -> request::any::Any {
+        request::Request {
+            id: Uuid::new_v4(),
+            body: request::any::Kind::RestoreToken(request::restore_token::RestoreToken {
+                auth_id: None,
+                access_token: None,
+                extended: HashMap::new(),
+                need_user_info: false,
+            }),
+        }
+    }
+
+    async fn wait_for_state(socket: &Socket, state: status::ConnectionState) {
+        let mut status = socket.status.clone();
+        time::timeout(FAIL_FAST, status.wait_for(|status| status.state == state))
+            .await
+            .expect("state was not reached in time")
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn reconnects_when_peer_sends_close_frame() {
+        let (connected_tx, connected_rx) = oneshot::channel();
+        let addr = serve_once(|mut ws| async move {
+            let _ = connected_rx.await;
+            ws.close(None).await.unwrap();
+        })
+        .await;
+
+        let socket = socket(addr);
+        wait_for_state(&socket, status::ConnectionState::Connected).await;
+
+        connected_tx.send(()).unwrap();
+        wait_for_state(&socket, status::ConnectionState::Connecting).await;
+        assert_eq!(
+            socket.status().last_error.as_deref(),
+            Some("connection lost")
+        );
+
+        socket.shutdown().await;
+    }
+
+    #[tokio::test]
+    async fn fails_in_flight_requests_when_stream_ends() {
+        let addr = serve_once(|mut ws| async move {
+            // Drop the connection without a close frame once the request arrives.
+            let _ = ws.next().await;
+        })
+        .await;
+
+        let socket = socket(addr);
+        wait_for_state(&socket, status::ConnectionState::Connected).await;
+
+        let result = time::timeout(
+            FAIL_FAST,
+            socket.send_request(request(), Duration::from_secs(30)),
+        )
+        .await
+        .expect("request waited for its timeout");
+
+        assert!(matches!(result, Err(Error::Disconnected)));
+        assert_eq!(socket.status().state, status::ConnectionState::Connecting);
+
+        socket.shutdown().await;
+    }
+}
@@ -1,43 +1,235 @@
 use std::time::Duration;
 
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+pub const DEFAULT_PING_INTERVAL: Duration = Duration::from_secs(30);
+pub const DEFAULT_PONG_TIMEOUT: Duration = Duration::from_secs(10);
+pub const DEFAULT_CAPACITY: usize = 256;
+pub const DEFAULT_CONCURRENCY: usize = 5120;
+
 #[derive(Clone)]
 pub struct SocketOptions {
+    /// Name identifying the socket in metrics, the address is used if absent.
+    pub name: Option<String>,
+    /// Time to wait for a response to a request.
     pub timeout: Duration,
-    pub reconnection_timeout: Duration,
+    /// Policy applied between reconnection attempts.
+    pub reconnect: ReconnectPolicy,
+    /// How long new requests wait for a reconnection, `None` fails them immediately.
+    pub reconnect_wait: Option<Duration>,
+    /// Interval between keepalive pings, `None` disables them.
+    pub ping_interval: Option<Duration>,
+    /// Time the peer has to answer a ping before the connection is considered dead.
+    pub pong_timeout: Duration,
+    /// Capacity of the internal event channels.
+    pub capacity: usize,
+    /// Maximum number of messages processed concurrently per direction.
+    pub concurrency: usize,
 }
 
 impl SocketOptions {
     pub fn builder() -> SocketOptionsBuilder {
         SocketOptionsBuilder {
+            name: None,
             timeout: None,
-            reconnection_timeout: None,
+            reconnect: None,
+            reconnect_wait: None,
+            ping_interval: Some(DEFAULT_PING_INTERVAL),
+            pong_timeout: None,
+            capacity: None,
+            concurrency: None,
         }
     }
 }
 
 pub struct SocketOptionsBuilder {
+    name: Option<String>,
     timeout: Option<Duration>,
-    reconnection_timeout: Option<Duration>,
+    reconnect: Option<ReconnectPolicy>,
+    reconnect_wait: Option<Duration>,
+    ping_interval: Option<Duration>,
+    pong_timeout: Option<Duration>,
+    capacity: Option<usize>,
+    concurrency: Option<usize>,
 }
 
 impl SocketOptionsBuilder {
+    pub fn with_name(mut self, name: impl Into<Option<String>>) -> SocketOptionsBuilder {
+        self.name = name.into();
+        self
+    }
+
     pub fn with_timeout(mut self, timeout: impl Into<Option<Duration>>) -> SocketOptionsBuilder {
         self.timeout = timeout.into();
         self
     }
 
-    pub fn with_reconnection_timeout(
+    pub fn with_reconnect_policy(
+        mut self,
+        reconnect: impl Into<Option<ReconnectPolicy>>,
+    ) -> SocketOptionsBuilder {
+        self.reconnect = reconnect.into();
+        self
+    }
+
+    pub fn with_reconnect_wait(
+        mut self,
+        reconnect_wait: impl Into<Option<Duration>>,
+    ) -> SocketOptionsBuilder {
+        self.reconnect_wait = reconnect_wait.into();
+        self
+    }
+
+    /// Sets the keepalive ping interval, `None` disables keepalive pings.
+    pub fn with_ping_interval(
+        mut self,
+        ping_interval: impl Into<Option<Duration>>,
+    ) -> SocketOptionsBuilder {
+        self.ping_interval = ping_interval.into();
+        self
+    }
+
+    pub fn with_pong_timeout(
         mut self,
-        reconnection_timeout: impl Into<Option<Duration>>,
+        pong_timeout: impl Into<Option<Duration>>,
     ) -> SocketOptionsBuilder {
-        self.reconnection_timeout = reconnection_timeout.into();
+        self.pong_timeout = pong_timeout.into();
+        self
+    }
+
+    pub fn with_capacity(mut self, capacity: impl Into<Option<usize>>) -> SocketOptionsBuilder {
+        self.capacity = capacity.into();
+        self
+    }
+
+    pub fn with_concurrency(
+        mut self,
+        concurrency: impl Into<Option<usize>>,
+    ) -> SocketOptionsBuilder {
+        self.concurrency = concurrency.into();
         self
     }
 
     pub fn build(self) -> SocketOptions {
         SocketOptions {
-            timeout: Duration::from_secs(5),
-            reconnection_timeout: Duration::from_secs(5),
+            name: self.name,
+            timeout: self.timeout.unwrap_or(DEFAULT_TIMEOUT),
+            reconnect: self.reconnect.unwrap_or_default(),
+            reconnect_wait: self.reconnect_wait,
+            ping_interval: self.ping_interval.filter(|interval| !interval.is_zero()),
+            pong_timeout: self.pong_timeout.unwrap_or(DEFAULT_PONG_TIMEOUT),
+            // Zero-sized channels and join sets would never make progress.
+            capacity: self.capacity.unwrap_or(DEFAULT_CAPACITY).max(1),
+            concurrency: self.concurrency.unwrap_or(DEFAULT_CONCURRENCY).max(1),
         }
     }
 }
+
+/// Exponential backoff with jitter used between reconnection attempts.
+#[derive(Clone, Debug)]
+pub struct ReconnectPolicy {
+    /// Delay before the second attempt, the first one is made immediately.
+    pub initial_delay: Duration,
+    /// Factor the delay grows by after every failed attempt.
+    pub multiplier: f64,
+    /// Upper bound of the delay before jitter is applied.
+    pub max_delay: Duration,
+    /// Fraction of the delay randomly added or subtracted, in `0.0..=1.0`.
+    pub jitter: f64,
+    /// Number of failed attempts after which reconnecting is given up, `None` retries forever.
+    ///
+    /// `Some(0)` behaves like `Some(1)`: the first failure already gives up.
+    pub max_attempts: Option<u32>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        ReconnectPolicy {
+            initial_delay: Duration::from_secs(1),
+            multiplier: 2.0,
+            max_delay: Duration::from_secs(60),
+            jitter: 0.2,
+            max_attempts: None,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    /// Returns the delay to wait after the given number of consecutive failed attempts.
+    pub fn delay(&self, failed_attempts: u32) -> Duration {
+        let exponent = failed_attempts.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let delay = (self.initial_delay.as_secs_f64() * self.multiplier.max(1.0).powi(exponent))
+            .min(self.max_delay.as_secs_f64());
+
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let factor = match jitter > 0.0 {
+            true => 1.0 + rand::random_range(-jitter..=jitter),
+            false => 1.0,
+        };
+
+        Duration::try_from_secs_f64(delay * factor).unwrap_or(self.max_delay)
+    }
+
+    pub fn gives_up_after(&self, failed_attempts: u32) -> bool {
+        self.max_attempts
+            .is_some_and(|max_attempts| failed_attempts >= max_attempts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(jitter: f64, max_attempts: Option<u32>) -> ReconnectPolicy {
+        ReconnectPolicy {
+            initial_delay: Duration::from_secs(1),
+            multiplier: 2.0,
+            max_delay: Duration::from_secs(60),
+            jitter,
+            max_attempts,
+        }
+    }
+
+    #[test]
+    fn delay_grows_exponentially_from_initial_delay() {
+        let policy = policy(0.0, None);
+
+        assert_eq!(policy.delay(1), Duration::from_secs(1));
+        assert_eq!(policy.delay(2), Duration::from_secs(2));
+        assert_eq!(policy.delay(3), Duration::from_secs(4));
+        assert_eq!(policy.delay(6), Duration::from_secs(32));
+    }
+
+    #[test]
+    fn delay_is_capped_at_max_delay() {
+        let policy = policy(0.0, None);
+
+        assert_eq!(policy.delay(7), Duration::from_secs(60));
+        assert_eq!(policy.delay(100), Duration::from_secs(60));
+        assert_eq!(policy.delay(u32::MAX), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn jitter_stays_within_bounds() {
+        let policy = policy(0.25, None);
+
+        for _ in 0..1000 {
+            let delay = policy.delay(3).as_secs_f64();
+            assert!((3.0 - 1e-9..=5.0 + 1e-9).contains(&delay), "{delay}");
+
+            // Jitter is applied on top of the capped delay.
+            let delay = policy.delay(100).as_secs_f64();
+            assert!((45.0 - 1e-9..=75.0 + 1e-9).contains(&delay), "{delay}");
+        }
+    }
+
+    #[test]
+    fn max_attempts_ends_retries() {
+        let policy = policy(0.0, Some(3));
+        assert!(!policy.gives_up_after(1));
+        assert!(!policy.gives_up_after(2));
+        assert!(policy.gives_up_after(3));
+
+        let policy = ReconnectPolicy::default();
+        assert!(!policy.gives_up_after(u32::MAX));
+    }
+}
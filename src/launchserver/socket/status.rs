@@ -0,0 +1,30 @@
+use std::time::SystemTime;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConnectionState {
+    /// The WebSocket is open.
+    Connected,
+    /// A connection is being (re)established.
+    Connecting,
+    /// Connection attempts were given up or the socket was shut down.
+    Disconnected,
+}
+
+/// Snapshot of the WebSocket connection health.
+#[derive(Clone, Debug)]
+pub struct Status {
+    pub state: ConnectionState,
+    /// The most recent connection failure, kept after a successful reconnection.
+    pub last_error: Option<String>,
+    pub last_connected_at: Option<SystemTime>,
+}
+
+impl Default for Status {
+    fn default() -> Self {
+        Status {
+            state: ConnectionState::Connecting,
+            last_error: None,
+            last_connected_at: None,
+        }
+    }
+}
@@ -14,10 +14,13 @@ pub struct Request<T: Serialize + DeserializeOwned> {
 pub mod any {
     use crate::launchserver::types::request::{
         Request,
+        auth,
         batch_profiles_by_usernames,
         check_server,
+        exit,
         get_profile_by_username,
         get_profile_by_uuid,
+        join_server,
         restore_token,
     };
     use serde::{Deserialize, Serialize};
@@ -33,6 +36,9 @@ pub mod any {
         #[serde(rename = "checkServer")]
         CheckServer(check_server::CheckServer),
 
+        #[serde(rename = "joinServer")]
+        JoinServer(join_server::JoinServer),
+
         #[serde(rename = "profileByUUID")]
         GetProfileByUuid(get_profile_by_uuid::GetProfileByUuid),
 
@@ -41,6 +47,28 @@ pub mod any {
 
         #[serde(rename = "batchProfileByUsername")]
         BatchProfilesByUsernames(batch_profiles_by_usernames::BatchProfilesByUsernames),
+
+        #[serde(rename = "auth")]
+        Auth(auth::Auth),
+
+        #[serde(rename = "exit")]
+        Exit(exit::Exit),
+    }
+
+    impl Kind {
+        /// Returns the request type as sent in the `type` field.
+        pub fn name(&self) -> &'static str {
+            match self {
+                Kind::RestoreToken(_) => "restore",
+                Kind::CheckServer(_) => "checkServer",
+                Kind::JoinServer(_) => "joinServer",
+                Kind::GetProfileByUuid(_) => "profileByUUID",
+                Kind::GetProfileByUsername(_) => "profileByUsername",
+                Kind::BatchProfilesByUsernames(_) => "batchProfileByUsername",
+                Kind::Auth(_) => "auth",
+                Kind::Exit(_) => "exit",
+            }
+        }
     }
 }
 
@@ -50,6 +78,12 @@ pub mod restore_token {
 
     #[derive(Serialize, Deserialize, Debug, Clone)]
     pub struct RestoreToken {
+        #[serde(rename = "authId", skip_serializing_if = "Option::is_none")]
+        pub auth_id: Option<String>,
+
+        #[serde(rename = "accessToken", skip_serializing_if = "Option::is_none")]
+        pub access_token: Option<String>,
+
         pub extended: HashMap<String, String>,
 
         #[serde(rename = "needUserInfo")]
@@ -80,6 +114,21 @@ pub mod check_server {
     }
 }
 
+pub mod join_server {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, Clone)]
+    pub struct JoinServer {
+        pub username: String,
+
+        #[serde(rename = "accessToken")]
+        pub access_token: String,
+
+        #[serde(rename = "serverID")]
+        pub server_id: String,
+    }
+}
+
 pub mod get_profile_by_uuid {
     use serde::{Deserialize, Serialize};
     use uuid::Uuid;
@@ -112,3 +161,48 @@ pub mod batch_profiles_by_usernames {
         pub username: String,
     }
 }
+
+pub mod auth {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, Clone)]
+    pub struct Auth {
+        pub login: String,
+        pub password: Password,
+
+        #[serde(rename = "auth_id")]
+        pub auth_id: String,
+
+        #[serde(rename = "getSession")]
+        pub get_session: bool,
+
+        #[serde(rename = "authType")]
+        pub auth_type: AuthType,
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone)]
+    #[serde(tag = "type")]
+    pub enum Password {
+        #[serde(rename = "plain")]
+        Plain { password: String },
+    }
+
+    #[derive(Serialize, Deserialize, Debug, Clone)]
+    pub enum AuthType {
+        #[serde(rename = "CLIENT")]
+        Client,
+
+        #[serde(rename = "API")]
+        Api,
+    }
+}
+
+pub mod exit {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Debug, Clone)]
+    pub struct Exit {
+        #[serde(rename = "exitAll")]
+        pub exit_all: bool,
+    }
+}
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize, de::DeserializeOwned};
 use uuid::Uuid;
 
-#[derive(Serialize, Deserialize, Debug)]
+#[derive(Serialize, Deserialize, Clone, Debug)]
 #[serde(bound = "T: Serialize + DeserializeOwned")]
 pub struct Response<T: Serialize + DeserializeOwned> {
     #[serde(rename = "requestUUID")]
@@ -14,18 +14,21 @@ pub struct Response<T: Serialize + DeserializeOwned> {
 pub mod any {
     use crate::launchserver::types::response::{
         Response,
+        auth,
         batch_profiles_by_usernames,
         check_server,
         error,
+        exit,
         get_profile_by_username,
         get_profile_by_uuid,
+        join_server,
         restore_token,
     };
     use serde::{Deserialize, Serialize};
 
     pub type Any = Response<Kind>;
 
-    #[derive(Serialize, Deserialize, Debug)]
+    #[derive(Serialize, Deserialize, Clone, Debug)]
     #[serde(tag = "type")]
     pub enum Kind {
         #[serde(rename = "restore")]
@@ -34,6 +37,9 @@ pub mod any {
         #[serde(rename = "checkServer")]
         CheckServer(check_server::CheckServer),
 
+        #[serde(rename = "joinServer")]
+        JoinServer(join_server::JoinServer),
+
         #[serde(rename = "profileByUUID")]
         GetProfileByUuid(get_profile_by_uuid::GetProfileByUuid),
 
@@ -43,6 +49,12 @@ pub mod any {
         #[serde(rename = "batchProfileByUsername")]
         BatchProfilesByUsernames(batch_profiles_by_usernames::BatchProfilesByUsernames),
 
+        #[serde(rename = "auth")]
+        Auth(auth::Auth),
+
+        #[serde(rename = "exit")]
+        Exit(exit::Exit),
+
         #[serde(rename = "error")]
         Error(error::Error),
     }
@@ -51,13 +63,13 @@ pub mod any {
 pub mod error {
     use serde::{Deserialize, Serialize};
 
-    #[derive(Serialize, Deserialize, Debug)]
+    #[derive(Serialize, Deserialize, Clone, Debug)]
     pub struct Error {
         #[serde(rename = "error")]
         pub kind: Kind,
     }
 
-    #[derive(Serialize, Deserialize, Debug)]
+    #[derive(Serialize, Deserialize, Clone, Debug)]
     pub enum Kind {
         #[serde(rename = "User not found")]
         UserNotFound,
@@ -74,12 +86,61 @@ pub mod error {
 }
 
 pub mod restore_token {
+    use crate::launchserver::types::response::base;
     use serde::{Deserialize, Serialize};
 
-    #[derive(Serialize, Deserialize, Debug)]
+    #[derive(Serialize, Deserialize, Clone, Debug)]
     pub struct RestoreToken {
         #[serde(rename = "invalidTokens")]
         pub invalid_tokens: Vec<String>,
+
+        #[serde(rename = "userInfo", default, skip_serializing_if = "Option::is_none")]
+        pub user_info: Option<UserInfo>,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug)]
+    pub struct UserInfo {
+        #[serde(rename = "playerProfile")]
+        pub player_profile: Option<base::profile::Profile>,
+
+        #[serde(rename = "accessToken")]
+        pub access_token: Option<String>,
+    }
+}
+
+pub mod auth {
+    use crate::launchserver::types::response::base;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Clone, Debug)]
+    pub struct Auth {
+        #[serde(rename = "playerProfile")]
+        pub player_profile: Option<base::profile::Profile>,
+
+        #[serde(rename = "accessToken")]
+        pub access_token: Option<String>,
+
+        pub oauth: Option<OAuth>,
+    }
+
+    #[derive(Serialize, Deserialize, Clone, Debug)]
+    pub struct OAuth {
+        #[serde(rename = "accessToken")]
+        pub access_token: String,
+
+        #[serde(rename = "refreshToken")]
+        pub refresh_token: String,
+
+        pub expire: i64,
+    }
+}
+
+pub mod exit {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Clone, Debug)]
+    pub struct Exit {
+        pub reason: String,
     }
 }
 
@@ -87,7 +148,7 @@ pub mod check_server {
     use serde::{Deserialize, Serialize};
     use uuid::Uuid;
 
-    #[derive(Serialize, Deserialize, Debug)]
+    #[derive(Serialize, Deserialize, Clone, Debug)]
     pub struct CheckServer {
         pub uuid: Uuid,
 
@@ -95,18 +156,27 @@ pub mod check_server {
         pub profile: Profile,
     }
 
-    #[derive(Serialize, Deserialize, Debug)]
+    #[derive(Serialize, Deserialize, Clone, Debug)]
     pub struct Profile {
         pub uuid: Uuid,
         pub username: String,
     }
 }
 
+pub mod join_server {
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize, Clone, Debug)]
+    pub struct JoinServer {
+        pub allow: bool,
+    }
+}
+
 pub mod get_profile_by_uuid {
     use crate::launchserver::types::response::base;
     use serde::{Deserialize, Serialize};
 
-    #[derive(Serialize, Deserialize, Debug)]
+    #[derive(Serialize, Deserialize, Clone, Debug)]
     pub struct GetProfileByUuid {
         #[serde(rename = "playerProfile")]
         pub player_profile: base::profile::Profile,
@@ -117,7 +187,7 @@ pub mod get_profile_by_username {
     use crate::launchserver::types::response::base;
     use serde::{Deserialize, Serialize};
 
-    #[derive(Serialize, Deserialize, Debug)]
+    #[derive(Serialize, Deserialize, Clone, Debug)]
     pub struct GetProfileByUsername {
         #[serde(rename = "playerProfile")]
         pub player_profile: base::profile::Profile,
@@ -129,14 +199,14 @@ pub mod base {
         use serde::{Deserialize, Serialize};
         use uuid::Uuid;
 
-        #[derive(Serialize, Deserialize, Debug)]
+        #[derive(Serialize, Deserialize, Clone, Debug)]
         pub struct Profile {
             pub uuid: Uuid,
             pub username: String,
             pub assets: Assets,
         }
 
-        #[derive(Serialize, Deserialize, Debug)]
+        #[derive(Serialize, Deserialize, Clone, Debug)]
         pub struct Assets {
             #[serde(rename = "SKIN", skip_serializing_if = "Option::is_none")]
             pub skin: Option<skin::Skin>,
@@ -148,7 +218,7 @@ pub mod base {
         pub mod skin {
             use serde::{Deserialize, Serialize};
 
-            #[derive(Serialize, Deserialize, Debug)]
+            #[derive(Serialize, Deserialize, Clone, Debug)]
             pub struct Skin {
                 pub url: url::Url,
                 pub digest: String,
@@ -160,13 +230,13 @@ pub mod base {
             pub mod metadata {
                 use serde::{Deserialize, Serialize};
 
-                #[derive(Serialize, Deserialize, Debug)]
+                #[derive(Serialize, Deserialize, Clone, Debug)]
                 pub struct Metadata {
                     #[serde(default)]
                     pub model: Model,
                 }
 
-                #[derive(Serialize, Deserialize, Default, Debug, Eq, PartialEq)]
+                #[derive(Serialize, Deserialize, Clone, Default, Debug, Eq, PartialEq)]
                 pub enum Model {
                     #[serde(rename = "slim")]
                     Slim,
@@ -181,7 +251,7 @@ pub mod base {
         pub mod cape {
             use serde::{Deserialize, Serialize};
 
-            #[derive(Serialize, Deserialize, Debug)]
+            #[derive(Serialize, Deserialize, Clone, Debug)]
             pub struct Cape {
                 pub url: url::Url,
                 pub digest: String,
@@ -194,7 +264,7 @@ pub mod batch_profiles_by_usernames {
     use crate::launchserver::types::response::base::profile;
     use serde::{Deserialize, Serialize};
 
-    #[derive(Serialize, Deserialize, Debug)]
+    #[derive(Serialize, Deserialize, Clone, Debug)]
     pub struct BatchProfilesByUsernames {
         #[serde(rename = "playerProfiles")]
         pub player_profiles: Vec<Option<profile::Profile>>,
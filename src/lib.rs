@@ -4,3 +4,4 @@ pub mod http;
 pub mod keypair;
 pub mod launchserver;
 pub mod logging;
+pub mod metrics;
@@ -1,8 +1,23 @@
-use std::{fs, io, path::Path, time};
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time,
+};
 
 use snafu::{ResultExt, Snafu};
 use tracing::Level;
-use tracing_subscriber::{Layer, filter, fmt, layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_appender::rolling;
+use tracing_subscriber::{
+    Layer,
+    filter,
+    fmt::{self, writer::BoxMakeWriter},
+    layer::SubscriberExt,
+    util::SubscriberInitExt,
+};
+
+use crate::config;
 
 #[derive(Debug, Snafu)]
 pub enum InitLoggingError {
@@ -17,45 +32,205 @@ pub enum InitLoggingError {
         #[snafu(source)]
         source: io::Error,
     },
+
+    #[snafu(display("creating time-rotated log file"))]
+    RollingFile {
+        #[snafu(source)]
+        source: rolling::InitError,
+    },
+
+    #[snafu(display("creating size-rotated log file"))]
+    SizeRollingFile {
+        #[snafu(source)]
+        source: io::Error,
+    },
 }
 
-pub fn load(dir: &Path) -> Result<(), InitLoggingError> {
+const LOG_FILE_PREFIX: &str = "debug";
+const LOG_FILE_SUFFIX: &str = "log";
+
+pub fn load(dir: &Path, options: &config::logging::Logging) -> Result<(), InitLoggingError> {
     tracing_subscriber::registry()
-        .with(
-            fmt::layer().with_filter(
-                filter::EnvFilter::builder()
-                    .with_default_directive(Level::INFO.into())
-                    .with_env_var("LOG_LEVEL")
-                    .from_env()
-                    .context(InvalidEnvVariableSnafu)?,
-            ),
-        )
+        .with(formatted(fmt::layer(), options.console.format).with_filter(env_filter()?))
         .with({
-            let timestamp = time::SystemTime::now()
-                .duration_since(time::UNIX_EPOCH)
-                .unwrap()
-                .as_secs();
-
-            let file = {
-                if let Err(err) = fs::create_dir(dir)
-                    && err.kind() != io::ErrorKind::AlreadyExists
-                {
-                    return Err(InitLoggingError::DirCreation { source: err });
-                }
+            let options = &options.file;
+
+            fs::create_dir_all(dir).context(DirCreationSnafu)?;
 
-                fs::OpenOptions::new()
-                    .append(true)
-                    .create(true)
-                    .open(format!("logs/debug-{}.log", timestamp))
-                    .unwrap()
+            let writer = match options.rotation {
+                config::logging::Rotation::Hourly => {
+                    BoxMakeWriter::new(rolling_file(dir, rolling::Rotation::HOURLY, options)?)
+                }
+                config::logging::Rotation::Daily => {
+                    BoxMakeWriter::new(rolling_file(dir, rolling::Rotation::DAILY, options)?)
+                }
+                config::logging::Rotation::Size => BoxMakeWriter::new(Mutex::new(
+                    SizeRollingFile::new(
+                        dir,
+                        options.max_file_size_mb * 1024 * 1024,
+                        options.max_files,
+                    )
+                    .context(SizeRollingFileSnafu)?,
+                )),
             };
 
-            fmt::layer()
-                .with_ansi(false)
-                .with_writer(file)
-                .with_filter(filter::LevelFilter::from_level(Level::DEBUG))
+            formatted(
+                fmt::layer().with_ansi(false).with_writer(writer),
+                options.format,
+            )
+            .with_filter(filter::LevelFilter::from_level(match options.level {
+                config::logging::Level::Trace => Level::TRACE,
+                config::logging::Level::Debug => Level::DEBUG,
+                config::logging::Level::Info => Level::INFO,
+                config::logging::Level::Warn => Level::WARN,
+                config::logging::Level::Error => Level::ERROR,
+            }))
         })
         .init();
 
     Ok(())
 }
+
+/// Logs to stderr only, for commands that must not touch the log files.
+pub fn load_stderr(options: &config::logging::Console) -> Result<(), InitLoggingError> {
+    tracing_subscriber::registry()
+        .with(
+            formatted(fmt::layer().with_writer(io::stderr), options.format)
+                .with_filter(env_filter()?),
+        )
+        .init();
+
+    Ok(())
+}
+
+fn env_filter() -> Result<filter::EnvFilter, InitLoggingError> {
+    filter::EnvFilter::builder()
+        .with_default_directive(Level::INFO.into())
+        .with_env_var("LOG_LEVEL")
+        .from_env()
+        .context(InvalidEnvVariableSnafu)
+}
+
+fn formatted<S, W>(
+    layer: fmt::Layer<S, fmt::format::DefaultFields, fmt::format::Format, W>,
+    format: config::logging::Format,
+) -> Box<dyn Layer<S> + Send + Sync>
+where
+    S: tracing::Subscriber + for<'span> tracing_subscriber::registry::LookupSpan<'span>,
+    W: for<'writer> fmt::MakeWriter<'writer> + Send + Sync + 'static,
+{
+    match format {
+        config::logging::Format::Full => layer.boxed(),
+        config::logging::Format::Compact => layer.compact().boxed(),
+        config::logging::Format::Json => layer
+            .json()
+            .with_current_span(true)
+            .with_span_list(true)
+            .boxed(),
+    }
+}
+
+fn rolling_file(
+    dir: &Path,
+    rotation: rolling::Rotation,
+    options: &config::logging::File,
+) -> Result<rolling::RollingFileAppender, InitLoggingError> {
+    rolling::RollingFileAppender::builder()
+        .rotation(rotation)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(options.max_files)
+        .build(dir)
+        .context(RollingFileSnafu)
+}
+
+/// Log file that starts a new file once the current one reaches `max_size` bytes, keeping at
+/// most `max_files` files.
+struct SizeRollingFile {
+    dir: PathBuf,
+    max_size: u64,
+    max_files: usize,
+
+    file: fs::File,
+    size: u64,
+}
+
+impl SizeRollingFile {
+    fn new(dir: &Path, max_size: u64, max_files: usize) -> io::Result<SizeRollingFile> {
+        let rolling_file = SizeRollingFile {
+            dir: dir.to_path_buf(),
+            max_size,
+            max_files,
+
+            file: open_size_rolled_file(dir)?,
+            size: 0,
+        };
+        rolling_file.remove_old_files()?;
+
+        Ok(rolling_file)
+    }
+
+    fn roll(&mut self) -> io::Result<()> {
+        self.file = open_size_rolled_file(&self.dir)?;
+        self.size = 0;
+
+        self.remove_old_files()
+    }
+
+    fn remove_old_files(&self) -> io::Result<()> {
+        let mut files = fs::read_dir(&self.dir)?
+            .filter_map(Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| {
+                        name.starts_with(&format!("{}-", LOG_FILE_PREFIX))
+                            && name.ends_with(&format!(".{}", LOG_FILE_SUFFIX))
+                    })
+            })
+            .collect::<Vec<_>>();
+
+        // Names embed a fixed-width timestamp, so they sort from oldest to newest.
+        files.sort();
+
+        let excess = files.len().saturating_sub(self.max_files);
+        for path in &files[..excess] {
+            fs::remove_file(path)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn open_size_rolled_file(dir: &Path) -> io::Result<fs::File> {
+    let timestamp = time::SystemTime::now()
+        .duration_since(time::UNIX_EPOCH)
+        .unwrap()
+        .as_millis();
+
+    fs::OpenOptions::new()
+        .append(true)
+        .create(true)
+        .open(dir.join(format!(
+            "{}-{:013}.{}",
+            LOG_FILE_PREFIX, timestamp, LOG_FILE_SUFFIX
+        )))
+}
+
+impl Write for SizeRollingFile {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if self.size > 0 && self.size + buf.len() as u64 > self.max_size {
+            self.roll()?;
+        }
+
+        let written = self.file.write(buf)?;
+        self.size += written as u64;
+
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.file.flush()
+    }
+}
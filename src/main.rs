@@ -1,3 +1,4 @@
+use arc_swap::ArcSwap;
 use futures::StreamExt;
 use kinly::{
     args,
@@ -6,14 +7,29 @@ use kinly::{
     keypair,
     launchserver,
     logging,
+    metrics,
+};
+use notify::Watcher;
+use snafu::{OptionExt, Report, ResultExt, Snafu};
+use std::{
+    collections::HashMap,
+    fs,
+    future,
+    io,
+    os::unix::fs::{DirBuilderExt, FileTypeExt, PermissionsExt},
+    path,
+    sync::Arc,
+    time,
 };
-use snafu::{Report, ResultExt, Snafu};
-use std::{collections::HashMap, io, sync::Arc, time};
 use tokio::{
     net,
     signal::unix::{SignalKind, signal},
+    sync::mpsc,
 };
-use tracing::info;
+use tracing::{error, info, warn};
+
+/// Delay before reading a changed config file.
+const CONFIG_CHANGE_DEBOUNCE: time::Duration = time::Duration::from_millis(200);
 
 #[derive(Debug, Snafu)]
 enum ApplicationError {
@@ -29,18 +45,50 @@ enum ApplicationError {
         source: keypair::LoadKeyPairError,
     },
 
+    #[snafu(display("verifying keypair"))]
+    VerifyKeyPair {
+        #[snafu(source)]
+        source: keypair::VerifyKeyPairError,
+    },
+
     #[snafu(display("loading config"))]
     LoadConfig {
         #[snafu(source)]
         source: config::LoadConfigError,
     },
 
-    #[snafu(display("binding TCP listener"))]
+    #[snafu(display("server {} not found in config", name))]
+    UnknownServer { name: String },
+
+    #[snafu(display(
+        "server {} has no keys_dir and signs with the shared keys, rotate them with --shared",
+        name
+    ))]
+    SharedKeys { name: String },
+
+    #[snafu(display("pinging LaunchServer of server {}", name))]
+    Ping {
+        name: String,
+        #[snafu(source)]
+        source: Box<launchserver::error::Error>,
+    },
+
+    #[snafu(display("LaunchServer rejected the token of server {}", name))]
+    TokenRejected { name: String },
+
+    #[snafu(display("binding listener on {}", bind))]
     BindListener {
+        bind: config::Bind,
         #[snafu(source)]
         source: io::Error,
     },
 
+    #[snafu(display("watching config file"))]
+    WatchConfig {
+        #[snafu(source)]
+        source: notify::Error,
+    },
+
     #[snafu(display("serving HTTP API"))]
     ServeHttp {
         #[snafu(source)]
@@ -52,15 +100,81 @@ fn main() -> Report<ApplicationError> {
     Report::capture(common_main)
 }
 
+fn load_key_pair(dir: &path::Path) -> Result<keypair::KeyPair, ApplicationError> {
+    let key_pair = keypair::load_or_create_key_pair(dir).context(LoadKeyPairSnafu)?;
+    keypair::verify_key_pair(&key_pair).context(VerifyKeyPairSnafu)?;
+
+    Ok(key_pair)
+}
+
+fn socket_options(
+    name: &str,
+    socket: &config::server::socket::Socket,
+) -> launchserver::socket::SocketOptions {
+    let defaults = launchserver::socket::ReconnectPolicy::default();
+    let reconnect = launchserver::socket::ReconnectPolicy {
+        initial_delay: socket
+            .reconnect
+            .initial_delay_ms
+            .map_or(defaults.initial_delay, time::Duration::from_millis),
+        multiplier: socket.reconnect.multiplier.unwrap_or(defaults.multiplier),
+        max_delay: socket
+            .reconnect
+            .max_delay_ms
+            .map_or(defaults.max_delay, time::Duration::from_millis),
+        jitter: socket.reconnect.jitter.unwrap_or(defaults.jitter),
+        max_attempts: socket.reconnect.max_attempts.or(defaults.max_attempts),
+    };
+
+    let mut builder = launchserver::socket::SocketOptions::builder()
+        .with_name(name.to_string())
+        .with_timeout(socket.timeout_ms.map(time::Duration::from_millis))
+        .with_reconnect_policy(reconnect)
+        .with_reconnect_wait(socket.reconnect_wait_ms.map(time::Duration::from_millis))
+        .with_pong_timeout(socket.pong_timeout_ms.map(time::Duration::from_millis))
+        .with_capacity(socket.capacity)
+        .with_concurrency(socket.concurrency);
+
+    if let Some(ping_interval_ms) = socket.ping_interval_ms {
+        builder = builder.with_ping_interval(time::Duration::from_millis(ping_interval_ms));
+    }
+
+    builder.build()
+}
+
 fn common_main() -> Result<(), ApplicationError> {
     let args = args::load();
 
-    logging::load(&args.logs_dir).context(InitLoggingSnafu)?;
+    if let Some(command) = args.command.clone() {
+        // Commands only report to stderr, leaving the log files of the proxy untouched.
+        logging::load_stderr(&logging_options(&args, Default::default()).console)
+            .context(InitLoggingSnafu)?;
+
+        return match command {
+            args::Command::Check => check_config(&args.config_path),
+            // Clap requires exactly one of `--server` and `--shared`.
+            args::Command::Keygen { server, .. } => generate_key_pair(&args, server.as_deref()),
+            args::Command::PublicKey { server, base64 } => {
+                print_public_key(&args, server.as_deref(), base64)
+            }
+            args::Command::Ping { server } => {
+                runtime().block_on(ping_server(&args.config_path, &server))
+            }
+        };
+    }
+
+    // Logging is configured by the config, so it starts once the config is loaded.
+    let source = config::load_or_create_config(&args.config_path).context(LoadConfigSnafu)?;
+    let (config::ConfigSource::Created(config) | config::ConfigSource::Loaded(config)) = &source;
+    logging::load(
+        &args.logs_dir,
+        &logging_options(&args, config.logging.clone()),
+    )
+    .context(InitLoggingSnafu)?;
 
-    let key_pair =
-        keypair::load_or_create_key_pair(&args.data_dir.join("keys")).context(LoadKeyPairSnafu)?;
+    let key_pair = load_key_pair(&args.data_dir.join("keys"))?;
 
-    let config = match config::load_or_create_config(&args.config_path).context(LoadConfigSnafu)? {
+    let config = match source {
         config::ConfigSource::Created(config) => {
             info!(
                 "application config not found. created new ({:?}): {:?}",
@@ -72,69 +186,565 @@ fn common_main() -> Result<(), ApplicationError> {
         config::ConfigSource::Loaded(config) => config,
     };
 
-    let rt = tokio::runtime::Builder::new_multi_thread()
+    runtime().block_on(async_main(args, config, key_pair))
+}
+
+fn logging_options(
+    args: &args::Args,
+    mut logging: config::logging::Logging,
+) -> config::logging::Logging {
+    if let Some(format) = args.log_format {
+        logging.console.format = format;
+    }
+
+    logging
+}
+
+fn runtime() -> tokio::runtime::Runtime {
+    tokio::runtime::Builder::new_multi_thread()
         .enable_all()
         .build()
-        .unwrap();
-    rt.block_on(async_main(args, config, key_pair))
+        .unwrap()
+}
+
+fn find_server(
+    config: config::Config,
+    name: &str,
+) -> Result<config::server::Server, ApplicationError> {
+    config
+        .servers
+        .into_iter()
+        .find(|server| server.name == name)
+        .context(UnknownServerSnafu { name })
+}
+
+/// Returns the signing keys directory of `server`, or the shared one.
+fn keys_dir(args: &args::Args, server: Option<&str>) -> Result<path::PathBuf, ApplicationError> {
+    let shared = args.data_dir.join("keys");
+
+    let Some(name) = server else {
+        return Ok(shared);
+    };
+
+    let config = config::load_config(&args.config_path).context(LoadConfigSnafu)?;
+    Ok(find_server(config, name)?.keys_dir.unwrap_or(shared))
+}
+
+fn check_config(path: &path::Path) -> Result<(), ApplicationError> {
+    let config = config::load_config(path).context(LoadConfigSnafu)?;
+
+    println!(
+        "config {:?} is valid, {} server(s) configured",
+        path,
+        config.servers.len()
+    );
+
+    Ok(())
+}
+
+/// Rotates the keys of `server`, or the shared ones if `None`.
+///
+/// A server without its own `keys_dir` is refused, as rotating the shared keys on its behalf
+/// would silently rotate them for every other server using them.
+fn generate_key_pair(args: &args::Args, server: Option<&str>) -> Result<(), ApplicationError> {
+    let dir = match server {
+        Some(name) => {
+            let config = config::load_config(&args.config_path).context(LoadConfigSnafu)?;
+            find_server(config, name)?
+                .keys_dir
+                .context(SharedKeysSnafu { name })?
+        }
+        None => args.data_dir.join("keys"),
+    };
+
+    let key_pair = keypair::rotate_key_pair(&dir).context(LoadKeyPairSnafu)?;
+    keypair::verify_key_pair(&key_pair).context(VerifyKeyPairSnafu)?;
+
+    println!("generated new signing key in {:?}", dir);
+    if key_pair.previous_public.is_some() {
+        println!(
+            "the replaced key is kept as the previous one and advertised until the next rotation"
+        );
+    }
+    println!("reload the proxy config (SIGHUP) to start signing with the new key");
+
+    Ok(())
+}
+
+fn print_public_key(
+    args: &args::Args,
+    server: Option<&str>,
+    base64: bool,
+) -> Result<(), ApplicationError> {
+    // Inspecting keys must not generate them, a new key would not be advertised by the proxy.
+    let key_pair = keypair::load_key_pair(&keys_dir(args, server)?).context(LoadKeyPairSnafu)?;
+
+    match base64 {
+        true => println!(
+            "{}",
+            key_pair
+                .public
+                .lines()
+                .filter(|line| !line.starts_with("-----"))
+                .collect::<String>()
+        ),
+        false => print!("{}", key_pair.public),
+    }
+
+    Ok(())
+}
+
+/// Connects to the LaunchServer of `name` and checks that its token is accepted.
+async fn ping_server(config_path: &path::Path, name: &str) -> Result<(), ApplicationError> {
+    let config = config::load_config(config_path).context(LoadConfigSnafu)?;
+    let server = find_server(config, name)?;
+
+    let mut options = socket_options(&server.name, &server.socket);
+    // Wait for the first connection instead of failing while it is being established.
+    options.reconnect_wait = options.reconnect_wait.or(Some(options.timeout));
+
+    let client = launchserver::Client::new(
+        server.token,
+        server.api.clone(),
+        options,
+        launchserver::cache::CacheOptions {
+            ttl: time::Duration::ZERO,
+            negative_ttl: time::Duration::ZERO,
+            max_entries: 0,
+        },
+    );
+
+    let started_at = time::Instant::now();
+    let result = client.check_token().await;
+    client.shutdown().await;
+
+    let response = result.map_err(Box::new).context(PingSnafu { name })?;
+    if !response.invalid_tokens.is_empty() {
+        return TokenRejectedSnafu { name }.fail();
+    }
+
+    println!(
+        "{}: connected to {} and token accepted in {:?}",
+        name,
+        server.api,
+        started_at.elapsed()
+    );
+
+    Ok(())
 }
 
 async fn async_main(
-    _args: args::Args,
+    args: args::Args,
     config: config::Config,
     keypair: keypair::KeyPair,
 ) -> Result<(), ApplicationError> {
-    let addr = std::net::SocketAddr::from((config.binds.host, config.binds.port));
-    let listener = net::TcpListener::bind(addr)
-        .await
-        .context(BindListenerSnafu)?;
-    info!("proxy listening on address {}", addr);
+    let mut listeners = Vec::with_capacity(config.binds.as_slice().len());
+    for bind in config.binds.as_slice() {
+        let listener = bind_listener(bind)
+            .await
+            .context(BindListenerSnafu { bind: bind.clone() })?;
+        info!("proxy listening on address {}", bind);
 
-    let servers = config
-        .servers
-        .into_iter()
-        .map(|server| {
-            let name = server.name;
-            let server = state::Server {
-                key_pair: state::ServerKeyPair {
-                    private: keypair.private.clone(),
-                    public: keypair.public.clone(),
-                },
-                assets: match server.meta.assets {
-                    Assets::AllInOne(values) => values,
-                    Assets::Separated { mut skins, capes } => {
-                        skins.extend(capes);
-                        skins
-                    }
-                },
-                client: launchserver::Client::new(
-                    server.token,
-                    server.api,
-                    time::Duration::from_secs(5),
-                ),
-            };
-
-            (name, Arc::new(server))
+        listeners.push(listener);
+    }
+    let sockets = config
+        .binds
+        .as_slice()
+        .iter()
+        .filter_map(|bind| match bind {
+            config::Bind::Unix(unix) => Some(unix.path.clone()),
+            config::Bind::Tcp { .. } => None,
         })
-        .collect::<HashMap<_, _>>();
+        .collect::<Vec<_>>();
+
+    let mut servers = state::Servers::with_capacity(config.servers.len());
+    for server in &config.servers {
+        let key_pair = server_key_pair(server, &keypair)?;
+        let client = Arc::new(build_client(server));
+        let built = build_server(server.clone(), key_pair, client);
+        servers.insert(server.name.clone(), Arc::new(built));
+    }
+
+    let state = Arc::new(state::State {
+        servers: ArcSwap::from_pointee(servers),
+        routing: ArcSwap::from_pointee(routing(&config)),
+    });
 
-    let state = Arc::new(state::State { servers });
+    let mut applied = config;
+    let shared_keys_dir = args.data_dir.join("keys");
+    let mut config_changes = match args.watch_config {
+        true => Some(watch_config(&args.config_path).context(WatchConfigSnafu)?),
+        false => None,
+    };
 
+    let mut sighup = signal(SignalKind::hangup()).expect("failed to construct SIGHUP signal");
     let mut sigterm = signal(SignalKind::terminate()).expect("failed to construct SIGTERM signal");
     let mut sigint = signal(SignalKind::interrupt()).expect("failed to construct SIGINT signal");
 
-    tokio::select! {
-        v = http::init(listener, Arc::clone(&state)) => v.context(ServeHttpSnafu)?,
-        _ = sigterm.recv() => info!("SIGTERM received, application shutdown initiated."),
-        _ = sigint.recv() => info!("SIGINT received, application shutdown initiated."),
+    let serve = http::init(listeners, Arc::clone(&state));
+    tokio::pin!(serve);
+
+    loop {
+        let reason = tokio::select! {
+            v = &mut serve => {
+                v.context(ServeHttpSnafu)?;
+                break;
+            }
+            _ = sigterm.recv() => {
+                info!("SIGTERM received, application shutdown initiated.");
+                break;
+            }
+            _ = sigint.recv() => {
+                info!("SIGINT received, application shutdown initiated.");
+                break;
+            }
+            _ = sighup.recv() => "SIGHUP received",
+            Some(()) = next_config_change(&mut config_changes) => "config file changed",
+        };
+
+        info!("{}, reloading config", reason);
+        if let Err(err) =
+            reload_config(&args.config_path, &shared_keys_dir, &mut applied, &state).await
+        {
+            error!(
+                "config reload failed, keeping current servers: {}",
+                Report::from_error(err)
+            );
+        }
     }
 
-    let sockets = state.servers.values().map(|server| &server.client);
-    futures::stream::iter(sockets)
-        .for_each_concurrent(None, async |socket| socket.shutdown().await)
-        .await;
+    shutdown_clients(
+        state
+            .servers
+            .load()
+            .values()
+            .map(|server| Arc::clone(&server.client))
+            .collect(),
+    )
+    .await;
+
+    for path in sockets {
+        if let Err(err) = fs::remove_file(&path) {
+            warn!("failed to remove socket {}: {}", path.display(), err);
+        }
+    }
 
     info!("application successfully stopped. Exit...");
 
     Ok(())
 }
+
+/// Binds a listener, replacing a unix socket left behind by a previous run.
+async fn bind_listener(bind: &config::Bind) -> Result<http::Listener, io::Error> {
+    match bind {
+        config::Bind::Tcp { host, port } => net::TcpListener::bind((*host, *port))
+            .await
+            .map(http::Listener::Tcp),
+        config::Bind::Unix(unix) => {
+            remove_stale_socket(&unix.path).await?;
+
+            let listener = match unix.permissions() {
+                Some(mode) => bind_unix_with_mode(&unix.path, mode)?,
+                None => net::UnixListener::bind(&unix.path)?,
+            };
+
+            Ok(http::Listener::Unix(listener))
+        }
+    }
+}
+
+/// Removes a socket left behind by a previous run, refusing to take over one still served.
+async fn remove_stale_socket(path: &path::Path) -> Result<(), io::Error> {
+    if !fs::symlink_metadata(path).is_ok_and(|meta| meta.file_type().is_socket()) {
+        return Ok(());
+    }
+
+    match net::UnixStream::connect(path).await {
+        Ok(_) => Err(io::Error::new(
+            io::ErrorKind::AddrInUse,
+            "socket is served by another process",
+        )),
+        Err(err) if err.kind() == io::ErrorKind::ConnectionRefused => fs::remove_file(path),
+        Err(err) => Err(err),
+    }
+}
+
+/// Binds a unix socket in a private directory and moves it into place once its permissions
+/// are set, so it is never reachable with the permissions left by the umask.
+fn bind_unix_with_mode(path: &path::Path, mode: u32) -> Result<net::UnixListener, io::Error> {
+    // Moving the socket into place would silently replace whatever is there.
+    if fs::symlink_metadata(path).is_ok() {
+        return Err(io::Error::from(io::ErrorKind::AlreadyExists));
+    }
+
+    let parent = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+        .unwrap_or(path::Path::new("."));
+    let private = parent.join(format!(".kinly-{}.tmp", std::process::id()));
+    fs::DirBuilder::new().mode(0o700).create(&private)?;
+
+    let staged = private.join("socket");
+    let listener = net::UnixListener::bind(&staged).and_then(|listener| {
+        fs::set_permissions(&staged, fs::Permissions::from_mode(mode))?;
+        fs::rename(&staged, path)?;
+        Ok(listener)
+    });
+
+    // The staged socket is only left behind if binding failed midway.
+    let _ = fs::remove_file(&staged);
+    let _ = fs::remove_dir(&private);
+
+    listener
+}
+
+/// Builds the LaunchServer client of a server, spawning its connection.
+fn build_client(server: &config::server::Server) -> launchserver::Client {
+    launchserver::Client::new(
+        server.token.clone(),
+        server.api.clone(),
+        socket_options(&server.name, &server.socket),
+        launchserver::cache::CacheOptions {
+            ttl: time::Duration::from_secs(server.cache.ttl_secs),
+            negative_ttl: time::Duration::from_secs(server.cache.negative_ttl_secs),
+            max_entries: server.cache.max_entries,
+        },
+    )
+}
+
+/// Returns whether two configs of a server can share one LaunchServer client.
+fn same_connection(a: &config::server::Server, b: &config::server::Server) -> bool {
+    a.api == b.api && a.token == b.token && a.socket == b.socket && a.cache == b.cache
+}
+
+/// Loads the signing keys of a server, falling back to the shared ones without `keys_dir`.
+fn server_key_pair(
+    server: &config::server::Server,
+    shared: &keypair::KeyPair,
+) -> Result<keypair::KeyPair, ApplicationError> {
+    match &server.keys_dir {
+        Some(dir) => load_key_pair(dir),
+        None => Ok(shared.clone()),
+    }
+}
+
+/// Returns whether a served server already signs with `key_pair`.
+fn same_keys(served: &state::ServerKeyPair, key_pair: &keypair::KeyPair) -> bool {
+    served.public == key_pair.public && served.previous_public == key_pair.previous_public
+}
+
+/// Builds a served server around its LaunchServer client.
+fn build_server(
+    server: config::server::Server,
+    key_pair: keypair::KeyPair,
+    client: Arc<launchserver::Client>,
+) -> state::Server {
+    state::Server {
+        key_pair: state::ServerKeyPair {
+            private: key_pair.private,
+            public: key_pair.public,
+            previous_public: key_pair.previous_public,
+        },
+        assets: match server.meta.assets {
+            Assets::AllInOne(values) => values,
+            Assets::Separated { mut skins, capes } => {
+                skins.extend(capes);
+                skins
+            }
+        },
+        links: server.meta.links,
+        features: server.meta.features,
+        auth_id: server.auth_id,
+        client,
+    }
+}
+
+fn routing(config: &config::Config) -> state::Routing {
+    state::Routing {
+        public_url: config.public_url.clone(),
+        default_server: config.default_server.clone(),
+        trusted_proxies: config.trusted_proxies.clone(),
+        trust_unix_peers: config.trust_unix_peers,
+    }
+}
+
+/// Applies the config at `path`, keeping servers whose config and signing keys are unchanged.
+///
+/// Signing keys are read again from the shared `keys_dir` and from the `keys_dir` of every
+/// server, so keys rotated by `keygen` are picked up. Servers whose connection settings are
+/// unchanged keep their LaunchServer client, others get a new one. Everything is loaded
+/// before the served set is swapped, so a failure leaves the current servers untouched.
+/// Clients no longer used are shut down after the swap.
+async fn reload_config(
+    path: &path::Path,
+    keys_dir: &path::Path,
+    applied: &mut config::Config,
+    state: &state::State,
+) -> Result<(), ApplicationError> {
+    let config = config::load_config(path).context(LoadConfigSnafu)?;
+
+    let shared = load_key_pair(keys_dir)?;
+    let key_pairs = config
+        .servers
+        .iter()
+        .map(|server| server_key_pair(server, &shared))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    if config.binds != applied.binds {
+        warn!("binds changes are applied only after restart");
+    }
+
+    if config.logging != applied.logging {
+        warn!("logging changes are applied only after restart");
+    }
+
+    let applied_servers = applied
+        .servers
+        .iter()
+        .map(|server| (server.name.as_str(), server))
+        .collect::<HashMap<_, _>>();
+    let current = state.servers.load_full();
+
+    let mut servers = state::Servers::with_capacity(config.servers.len());
+    let mut connected = Vec::new();
+    let mut updated = 0;
+    for (server, key_pair) in config.servers.iter().zip(key_pairs) {
+        let applied_server = applied_servers.get(server.name.as_str()).copied();
+
+        let existing = match (current.get(&server.name), applied_server) {
+            (Some(existing), Some(applied_server))
+                if applied_server == server && same_keys(&existing.key_pair, &key_pair) =>
+            {
+                servers.insert(server.name.clone(), Arc::clone(existing));
+                continue;
+            }
+            (Some(existing), Some(applied_server)) if same_connection(applied_server, server) => {
+                Some(existing)
+            }
+            _ => None,
+        };
+
+        let client = match existing {
+            Some(existing) => {
+                if !same_keys(&existing.key_pair, &key_pair) {
+                    info!("signing keys of server {} changed", server.name);
+                }
+
+                updated += 1;
+                Arc::clone(&existing.client)
+            }
+            None => {
+                let client = Arc::new(build_client(server));
+                connected.push(Arc::clone(&client));
+                client
+            }
+        };
+
+        let server_state = build_server(server.clone(), key_pair, client);
+        servers.insert(server.name.clone(), Arc::new(server_state));
+    }
+
+    let previous = state.servers.swap(Arc::new(servers));
+    let next = state.servers.load();
+
+    let stale = previous
+        .values()
+        .map(|server| &server.client)
+        .filter(|client| {
+            !next
+                .values()
+                .any(|server| Arc::ptr_eq(&server.client, client))
+        })
+        .cloned()
+        .collect::<Vec<_>>();
+    let removed = previous
+        .keys()
+        .filter(|name| !next.contains_key(*name))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    if config.public_url != applied.public_url
+        || config.default_server != applied.default_server
+        || config.trusted_proxies != applied.trusted_proxies
+        || config.trust_unix_peers != applied.trust_unix_peers
+    {
+        state.routing.store(Arc::new(routing(&config)));
+        info!("public_url, default_server, trusted_proxies and trust_unix_peers updated");
+    }
+
+    info!(
+        "config reloaded: {} servers connected, {} updated, {} disconnected, {} kept",
+        connected.len(),
+        updated,
+        stale.len(),
+        next.len() - connected.len() - updated
+    );
+
+    *applied = config;
+    tokio::spawn(async move {
+        shutdown_clients(stale).await;
+
+        // Replaced clients share the series of their successors, so only removed servers
+        // are dropped.
+        for name in removed {
+            metrics::get().remove_server_gauges(&name);
+        }
+    });
+
+    Ok(())
+}
+
+async fn shutdown_clients(clients: Vec<Arc<launchserver::Client>>) {
+    futures::stream::iter(clients)
+        .for_each_concurrent(None, async |client| client.shutdown().await)
+        .await;
+}
+
+/// Watches the directory of the config file, as editors often replace the file instead of
+/// writing it in place.
+fn watch_config(
+    path: &path::Path,
+) -> Result<(notify::RecommendedWatcher, mpsc::Receiver<()>), notify::Error> {
+    // A single slot is enough, changes arriving before a reload are folded into it.
+    let (sender, receiver) = mpsc::channel(1);
+
+    let file_name = path.file_name().map(ToOwned::to_owned);
+    let mut watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+        let Ok(event) = event else {
+            return;
+        };
+
+        let is_config = event
+            .paths
+            .iter()
+            .any(|path| path.file_name() == file_name.as_deref());
+        if is_config && (event.kind.is_create() || event.kind.is_modify()) {
+            let _ = sender.try_send(());
+        }
+    })?;
+
+    let dir = path
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(path::Path::new("."));
+    watcher.watch(dir, notify::RecursiveMode::NonRecursive)?;
+
+    Ok((watcher, receiver))
+}
+
+/// Waits for the next config file change, never resolving when watching is disabled.
+async fn next_config_change(
+    changes: &mut Option<(notify::RecommendedWatcher, mpsc::Receiver<()>)>,
+) -> Option<()> {
+    let Some((_, receiver)) = changes else {
+        return future::pending().await;
+    };
+
+    receiver.recv().await?;
+
+    // Let the writer finish before reading the file, dropping the events it produces meanwhile.
+    tokio::time::sleep(CONFIG_CHANGE_DEBOUNCE).await;
+    while receiver.try_recv().is_ok() {}
+
+    Some(())
+}
@@ -0,0 +1,135 @@
+use prometheus::{
+    Encoder,
+    HistogramOpts,
+    HistogramVec,
+    IntCounterVec,
+    IntGaugeVec,
+    Opts,
+    Registry,
+    TextEncoder,
+};
+use std::sync::LazyLock;
+
+static METRICS: LazyLock<Metrics> = LazyLock::new(Metrics::new);
+
+/// Returns the process-wide metrics registry.
+pub fn get() -> &'static Metrics {
+    &METRICS
+}
+
+pub struct Metrics {
+    registry: Registry,
+
+    /// HTTP requests by `server_id`, `route` and `status`.
+    pub http_requests: IntCounterVec,
+    /// HTTP request latency by `server_id` and `route`.
+    pub http_request_duration: HistogramVec,
+
+    /// LaunchServer requests by `server_id`, `kind` and `outcome`.
+    pub launchserver_requests: IntCounterVec,
+    /// LaunchServer request latency by `server_id` and `kind`.
+    pub launchserver_request_duration: HistogramVec,
+    /// Connection losses by `server_id`.
+    pub launchserver_reconnects: IntCounterVec,
+    /// Requests awaiting a response by `server_id`.
+    pub launchserver_pending_callbacks: IntGaugeVec,
+
+    /// Cache lookups by `server_id`, `cache` and `result`.
+    pub cache_lookups: IntCounterVec,
+}
+
+impl Metrics {
+    fn new() -> Metrics {
+        let registry =
+            Registry::new_custom(Some("kinly".to_string()), None).expect("metrics prefix is valid");
+
+        let metrics = Metrics {
+            http_requests: IntCounterVec::new(
+                Opts::new("http_requests_total", "HTTP requests handled"),
+                &["server_id", "route", "status"],
+            )
+            .unwrap(),
+            http_request_duration: HistogramVec::new(
+                HistogramOpts::new("http_request_duration_seconds", "HTTP request latency"),
+                &["server_id", "route"],
+            )
+            .unwrap(),
+            launchserver_requests: IntCounterVec::new(
+                Opts::new(
+                    "launchserver_requests_total",
+                    "Requests sent to LaunchServer",
+                ),
+                &["server_id", "kind", "outcome"],
+            )
+            .unwrap(),
+            launchserver_request_duration: HistogramVec::new(
+                HistogramOpts::new(
+                    "launchserver_request_duration_seconds",
+                    "LaunchServer request latency",
+                ),
+                &["server_id", "kind"],
+            )
+            .unwrap(),
+            launchserver_reconnects: IntCounterVec::new(
+                Opts::new(
+                    "launchserver_reconnects_total",
+                    "Lost LaunchServer connections",
+                ),
+                &["server_id"],
+            )
+            .unwrap(),
+            launchserver_pending_callbacks: IntGaugeVec::new(
+                Opts::new(
+                    "launchserver_pending_callbacks",
+                    "LaunchServer requests awaiting a response",
+                ),
+                &["server_id"],
+            )
+            .unwrap(),
+            cache_lookups: IntCounterVec::new(
+                Opts::new("cache_lookups_total", "Profile cache lookups"),
+                &["server_id", "cache", "result"],
+            )
+            .unwrap(),
+            registry,
+        };
+
+        metrics.register();
+        metrics
+    }
+
+    fn register(&self) {
+        let collectors: [Box<dyn prometheus::core::Collector>; 7] = [
+            Box::new(self.http_requests.clone()),
+            Box::new(self.http_request_duration.clone()),
+            Box::new(self.launchserver_requests.clone()),
+            Box::new(self.launchserver_request_duration.clone()),
+            Box::new(self.launchserver_reconnects.clone()),
+            Box::new(self.launchserver_pending_callbacks.clone()),
+            Box::new(self.cache_lookups.clone()),
+        ];
+
+        for collector in collectors {
+            self.registry
+                .register(collector)
+                .expect("metric names are unique");
+        }
+    }
+
+    /// Stops exporting the gauges of a server removed from the config.
+    pub fn remove_server_gauges(&self, server_id: &str) {
+        let _ = self
+            .launchserver_pending_callbacks
+            .remove_label_values(&[server_id]);
+    }
+
+    /// Renders all metrics in the Prometheus text exposition format.
+    pub fn render(&self) -> String {
+        let mut buf = Vec::new();
+        TextEncoder::new()
+            .encode(&self.registry.gather(), &mut buf)
+            .expect("text encoding cannot fail");
+
+        String::from_utf8(buf).expect("text encoding produces UTF-8")
+    }
+}